@@ -0,0 +1,280 @@
+// Public library
+use robotics_lib::world::tile::Content;
+use robotics_lib::interface::Direction;
+use robotics_lib::world::environmental_conditions::WeatherType;
+
+// Standard library
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::State;
+
+/// Tunable parameters of the SaverBot
+/// All the values can be changed at runtime through `bot.config`
+///
+/// # Examples
+/// ```
+/// use saver_bot::new_saver_bot;
+///
+/// fn main () {
+///   let mut bot = new_saver_bot!(1);
+///   bot.config.filled_bank_cooldown = 50;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaverConfig {
+    // Ticks a just visited filled bank is not chosen again
+    pub filled_bank_cooldown: usize,
+    // Energy needed to act in a tick, for every state, `min_energy` for the states not listed
+    pub state_energy: HashMap<State, usize>,
+    pub min_energy: usize,
+    // Energy kept when going to the contents found while wandering
+    pub wander_traverse_reserve: usize,
+    // Energy kept for every single step or destroy
+    pub per_step_energy: usize,
+
+    // Backpack quantities of every content that make the bot change state
+    pub thresholds: HashMap<Content, Thresholds>,
+    // Coins that are enough to go saving when a free bank is within `near_bank_distance`,
+    // farther banks wait for the save threshold. None to always wait for the threshold
+    pub target_trip_load: Option<usize>,
+    pub near_bank_distance: usize,
+    // Deposit the coins at every tick while collecting within this distance of a free bank
+    pub collect_and_bank: bool,
+    pub collect_and_bank_radius: usize,
+    // Coins needed before going saving when it is not the save threshold asking for it
+    pub min_save_load: usize,
+    // Go to Finish as soon as the goal is reached, without collecting rocks
+    pub skip_rock_phase: bool,
+    // Ticks to wait after a trade before trading again
+    pub trade_cooldown_ticks: usize,
+
+    // Ticks given to reach the goal before moving on with what was saved, None to wait forever
+    pub goal_deadline_ticks: Option<usize>,
+
+    // Value of the contents, the wander goes first where there is more value for the distance
+    pub content_values: HashMap<Content, usize>,
+
+    // Radius of the search tool, and the wider one used when all the known banks are full
+    pub search_radius: usize,
+    pub full_banks_search_radius: usize,
+    // Ticks looking for a free bank with a full backpack before giving up on banking
+    pub bank_search_deadline: usize,
+    // Ticks without finding a bank before doubling the search radius, 0 to never widen
+    pub bank_search_retry_limit: usize,
+    pub max_search_radius: usize,
+
+    // Extra distance given to a bank for every dangerous tile around it
+    pub bank_hazard_penalty: usize,
+
+    // Every how many wandering ticks the known banks and the target are checked again, 0 to never do it
+    pub replan_interval: usize,
+
+    // Used banks remembered for the end of the run, the least deposited are forgotten first
+    pub max_used_banks: usize,
+
+    // Failed deposits in a row after which a bank is given up
+    pub max_put_failures: usize,
+
+    // Found contents reached in a single wander, None for no limit
+    pub max_wander_targets_per_tick: Option<usize>,
+
+    // How eagerly the contents around the bot are destroyed
+    pub destroy_strategy: DestroyStrategy,
+    // Conservative strategy: below this energy only the contents worth at least
+    // `conservative_min_value` are destroyed, and only with `conservative_min_room` free in the backpack
+    pub conservative_energy_margin: usize,
+    pub conservative_min_value: usize,
+    pub conservative_min_room: usize,
+
+    // Most of a content the bot carries, it stops destroying it once reached
+    pub collection_caps: HashMap<Content, usize>,
+    // Destroy what is around at every step while travelling to a position
+    pub collect_en_route: bool,
+    // With a full backpack go saving or trading before destroying more, otherwise just wait
+    pub free_space_before_destroy: bool,
+
+    // Recycle the carried trees while collecting rocks
+    pub recycle_trees: bool,
+
+    // Seen tiles with coins after which the bot stops exploring and collects them, None to always explore
+    pub enough_known_tiles: Option<usize>,
+
+    // How the bot chooses where to explore, with the distance between two waypoints
+    // and the size of the explored area for the systematic patterns
+    pub exploration_pattern: ExplorationPattern,
+    pub pattern_spacing: usize,
+    pub pattern_extent: usize,
+
+    // Weights of the quadrants when exploring, in the order of `DIRECTIONS`
+    // None to choose uniformly
+    pub quadrant_weights: Option<[f64; 4]>,
+
+    // Never step on lava, disable only if you know what you are doing
+    pub avoid_lava: bool,
+    // Times the bot goes around an obstacle while reaching a position
+    pub max_detours: usize,
+
+    // Corner of the bank the bot goes to before building the asphalt around it,
+    // the enclosure is laid out clockwise from there
+    pub approach_corner: Corner,
+    // Energy expected for every asphalted tile, an asphalt project starts only if it fits
+    pub asphalt_tile_energy: usize,
+
+    // Below `low_energy` the bot waits for one of the recharge weathers before doing anything expensive
+    pub wait_for_recharge_weather: bool,
+    pub low_energy: usize,
+    pub recharge_weathers: Vec<WeatherType>,
+
+    // Energy the bot can spend in total, then it stops. None for no limit
+    pub energy_budget: Option<usize>,
+
+    // Wall clock time a single tick can take, None for no limit
+    pub max_tick_duration: Option<Duration>,
+
+    // Record what the bot does in every tick, see `last_trace`
+    pub trace: bool,
+
+    // Pick up the coins next to the bot before depositing
+    pub sweep_before_deposit: bool,
+    // Directions tried when depositing in a bank, None to try the one toward the bank first
+    pub deposit_order: Option<Vec<Direction>>,
+
+    // State the bot goes to when the current one has nothing sensible to do
+    pub fallback_state: State,
+
+    // Contents deposited in the banks while saving, in order of priority
+    // The bot goes saving for any of them, and deposits first the one that comes first
+    pub bank_contents: Vec<Content>,
+}
+
+/// Quantities of a content in the backpack that make the bot change state, None to ignore it
+/// - save_at: Go saving while collecting coins
+/// - trade_at: Go trading while collecting coins
+/// - finish_at: Finish while collecting rocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Thresholds {
+    pub save_at: Option<usize>,
+    pub trade_at: Option<usize>,
+    pub finish_at: Option<usize>,
+}
+
+/// How the bot destroys the contents it is looking for
+/// - Greedy: Everything around is destroyed as soon as possible
+/// - Conservative: With low energy only valuable contents are destroyed, and only if they fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestroyStrategy {
+    Greedy,
+    Conservative,
+}
+
+/// How the bot explores while looking for something
+/// - Random: A random quadrant at every tick
+/// - Spiral: Squares growing around the starting point
+/// - Lawnmower: Row by row, from the top of the region (or of the explored area)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorationPattern {
+    Random,
+    Spiral,
+    Lawnmower,
+}
+
+/// Corner of a tile, as seen on the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// Rows and columns from a tile to the one at this corner
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Corner::TopLeft => (-1, -1),
+            Corner::TopRight => (-1, 1),
+            Corner::BottomLeft => (1, -1),
+            Corner::BottomRight => (1, 1),
+        }
+    }
+    /// Next corner going clockwise on the map
+    pub fn clockwise(&self) -> Corner {
+        match self {
+            Corner::TopLeft => Corner::TopRight,
+            Corner::TopRight => Corner::BottomRight,
+            Corner::BottomRight => Corner::BottomLeft,
+            Corner::BottomLeft => Corner::TopLeft,
+        }
+    }
+}
+
+impl Default for SaverConfig {
+    fn default() -> Self {
+        SaverConfig {
+            filled_bank_cooldown: 20,
+            state_energy: HashMap::from([
+                (State::Enjoying, 0),
+                (State::Finish, 500),
+            ]),
+            min_energy: 150,
+            wander_traverse_reserve: 400,
+            per_step_energy: 50,
+            thresholds: HashMap::from([
+                (Content::Coin(0), Thresholds { save_at: Some(12), ..Default::default() }),
+                (Content::Garbage(0), Thresholds { trade_at: Some(5), ..Default::default() }),
+                (Content::Rock(0), Thresholds { trade_at: Some(3), finish_at: Some(8), ..Default::default() }),
+            ]),
+            target_trip_load: None,
+            near_bank_distance: 10,
+            collect_and_bank: false,
+            collect_and_bank_radius: 4,
+            min_save_load: 1,
+            skip_rock_phase: false,
+            trade_cooldown_ticks: 5,
+            goal_deadline_ticks: None,
+            content_values: HashMap::from([
+                (Content::Coin(0), 10),
+                (Content::Rock(0), 3),
+                (Content::Garbage(0), 1),
+            ]),
+            search_radius: 2,
+            full_banks_search_radius: 6,
+            bank_search_deadline: 30,
+            bank_search_retry_limit: 10,
+            max_search_radius: 16,
+            bank_hazard_penalty: 5,
+            replan_interval: 50,
+            max_used_banks: 32,
+            max_put_failures: 3,
+            max_wander_targets_per_tick: None,
+            destroy_strategy: DestroyStrategy::Greedy,
+            conservative_energy_margin: 300,
+            conservative_min_value: 3,
+            conservative_min_room: 3,
+            collection_caps: HashMap::new(),
+            collect_en_route: false,
+            free_space_before_destroy: true,
+            recycle_trees: true,
+            enough_known_tiles: None,
+            exploration_pattern: ExplorationPattern::Random,
+            pattern_spacing: 5,
+            pattern_extent: 50,
+            quadrant_weights: None,
+            avoid_lava: true,
+            max_detours: 4,
+            approach_corner: Corner::BottomLeft,
+            asphalt_tile_energy: 50,
+            wait_for_recharge_weather: false,
+            low_energy: 300,
+            recharge_weathers: vec![WeatherType::Sunny],
+            energy_budget: None,
+            max_tick_duration: None,
+            trace: false,
+            sweep_before_deposit: false,
+            deposit_order: None,
+            fallback_state: State::CoinCollecting,
+            bank_contents: vec![Content::Coin(0)],
+        }
+    }
+}
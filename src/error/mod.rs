@@ -0,0 +1,25 @@
+// Public library
+use robotics_lib::interface::Direction;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::{Content, TileType};
+
+/// Failure of an action attempted by the bot during a tick
+/// - Go: The bot couldn't move in the direction
+/// - Put: The bot couldn't put the content in the direction
+/// - Destroy: The bot couldn't destroy the content in the direction
+/// - Trade: The recycle tool failed
+/// - DestroyZone: The destroy zone tool failed for the content
+/// - Asphalt: The asphalt tool failed
+/// - Unsafe: The bot refused to step on a dangerous tile
+/// - OutOfRegion: The bot refused to leave its region
+#[derive(Debug)]
+pub enum ActionError {
+    Go(Direction, LibError),
+    Put(Content, Direction, LibError),
+    Destroy(Direction, LibError),
+    Trade(String),
+    DestroyZone(Content, String),
+    Asphalt(String),
+    Unsafe(Direction, TileType),
+    OutOfRegion(Direction)
+}
@@ -1,11 +1,19 @@
 pub mod utils;
+pub mod config;
+pub mod error;
+pub mod trace;
+pub mod version;
 
 // Tools
 use charting_tools::ChartingTools; 
 use charting_tools::charted_coordinate::ChartedCoordinate;
 use charting_tools::charted_map::ChartedMap;
+#[cfg(feature = "audio")]
 use oxagaudiotool::OxAgAudioTool;
+#[cfg(feature = "audio")]
 use oxagaudiotool::sound_config::OxAgSoundConfig;
+#[cfg(feature = "audio")]
+use oxagaudiotool::error::error::OxAgAudioToolError;
 use recycle_by_ifrustrati::tool::recycle;
 use arrusticini_destroy_zone::DestroyZone;
 use asfalt_inator::{Asphaltinator, Shape};
@@ -18,19 +26,26 @@ use robotics_lib::world::coordinates::Coordinate;
 use robotics_lib::event::events::Event;
 use robotics_lib::runner::backpack::BackPack;
 use robotics_lib::energy::Energy;
-use robotics_lib::interface::{where_am_i, go, Direction, put, destroy};
+use robotics_lib::interface::{where_am_i, go, Direction, put, destroy, look_at_sky, robot_map};
 use robotics_lib::world::environmental_conditions::WeatherType;
 use robotics_lib::world::tile::{Tile, Content, TileType};
-use utils::clone_direction;
+use utils::{clone_direction, neighborhood_center, neighborhood_cells, direction_from_offset, manhattan, search_direction_toward, direction_toward, quadrant_index, seen_bucket, is_walkable, step_from, offset_from, bank_key, count_in};
 
 // Standard library
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use std::fmt::Debug;
-use std::ops::Range;
-use std::collections::BinaryHeap;
-use rand::Rng;
+use std::collections::{BinaryHeap, VecDeque};
+use std::time::Instant;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::distributions::{Distribution, WeightedIndex};
 
-use crate::utils::{COIN_LOOKING_FOR, ROCK_LOOKING_FOR, BANK_LOOKING_FOR, DIRECTIONS};
+use crate::config::{SaverConfig, DestroyStrategy, ExplorationPattern, Thresholds};
+use crate::error::ActionError;
+use crate::trace::TickTrace;
+use crate::version::ToolVersions;
+use crate::utils::{COIN_LOOKING_FOR, ROCK_LOOKING_FOR, BANK_LOOKING_FOR, DIRECTIONS, ASPHALT_PROJECTS};
 
 /// Represenst the state of the bot
 /// - Collecting: The bot is collecting phase
@@ -38,7 +53,7 @@ use crate::utils::{COIN_LOOKING_FOR, ROCK_LOOKING_FOR, BANK_LOOKING_FOR, DIRECTI
 /// - Saving: The bot is saving the resources to banks
 /// - Enjoying: The bot is enjoying the resources he collected
 /// 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum State {
     CoinCollecting,
     RockCollecting,
@@ -49,6 +64,58 @@ pub enum State {
     Finish
 }
 
+impl State {
+    /// Tells if the bot can start its life in this state
+    /// Enjoying and Finish need the bot to have already saved something
+    pub fn can_start(&self) -> bool {
+        !matches!(self, State::Enjoying | State::Finish)
+    }
+    /// Tells if the state machine is allowed to go from this state to `next`
+    /// - Staying in the same state is always allowed
+    /// - Enjoying is the end, every other state can stop there (shutdown, energy budget) and never leave it
+    /// - Saving can be started from every working state, as the shutdown does to bank what is left
+    /// - Finish goes back to CoinCollecting when the goal is raised
+    pub fn can_transition_to(&self, next: &State) -> bool {
+        if self == next {
+            return true;
+        }
+        if *self != State::Enjoying && matches!(next, State::Enjoying | State::Saving) {
+            return true;
+        }
+        match self {
+            State::CoinCollecting => matches!(next, State::Trading | State::RockCollecting | State::Finish),
+            State::RockCollecting => matches!(next, State::Finish | State::CoinCollecting),
+            State::Trading => matches!(next, State::CoinCollecting),
+            State::Saving => matches!(next, State::CoinCollecting | State::RockCollecting | State::BankSearching | State::Finish),
+            State::Enjoying => false,
+            State::BankSearching => matches!(next, State::RockCollecting | State::Finish),
+            State::Finish => matches!(next, State::CoinCollecting)
+        }
+    }
+}
+
+/// Status of a bank known by the bot
+/// - Free: The bank can still accept deposits
+/// - Filled: The bank is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankStatus {
+    Free,
+    Filled
+}
+
+/// Why the bot did nothing in the last tick
+/// - NotReady: The world has not sent the Ready event yet
+/// - LowEnergy: Waiting for the energy to recharge
+/// - Weather: Waiting for a weather that recharges faster
+/// - Done: Enjoying, there is nothing left to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleReason {
+    NotReady,
+    LowEnergy,
+    Weather,
+    Done
+}
+
 /// The SaverBot struct
 /// It has a Robot field, so it can be used as a robot
 /// It has a State field, so it can be used as a state machine
@@ -69,22 +136,117 @@ pub struct SaverBot{
     pub robot: Robot,
     pub state: State,
     pub goal: Option<usize>,
+    goal_set_tick: usize,
 
     // All the banks that the bot knows
     pub filled_banks: ChartedMap<Content>,
     pub free_banks: ChartedMap<Content>,
+    // Coordinates of the banks the bot deposited in, with the quantity deposited
     pub used_banks: HashMap<(usize, usize), usize>,
+    // Tick of the last deposit in every used bank
+    used_banks_tick: HashMap<(usize, usize), usize>,
+    // Known remaining capacity of the banks
+    bank_capacity: HashMap<(usize, usize), usize>,
+    // Total accepted by every bank
+    bank_accepted: HashMap<(usize, usize), usize>,
+    // Contents a bank accepts, the banks not in here accept everything
+    bank_accepts: HashMap<(usize, usize), Vec<Content>>,
+    // Consecutive failed deposits in every bank
+    put_failures: HashMap<(usize, usize), usize>,
+    // Called when a new bank is discovered
+    on_bank_found: Option<Box<dyn FnMut((usize, usize))>>,
+    // Banks reserved by this bot, and the reservations shared with the other bots
+    reserved: HashSet<(usize, usize)>,
+    reservations: Option<Arc<Mutex<HashSet<(usize, usize)>>>>,
+    // Tick of the last visit of the filled banks
+    bank_cooldowns: HashMap<(usize, usize), usize>,
 
     // Coins taken so far
     pub saved: usize,
+    // Everything deposited in the banks so far, by content
+    deposited: HashMap<Content, usize>,
+    shutting_down: bool,
+    last_trade_tick: Option<usize>,
 
     // Utility variables
     pub looking_for: Vec<Content>,
-    pub audio: OxAgAudioTool,
+    // Given by the user with `with_audio`, or created at the first sound played
+    // with the master volume and the pickup sounds set until then
+    #[cfg(feature = "audio")]
+    pub audio: Option<OxAgAudioTool>,
+    muted: bool,
+    master_volume: f32,
+    // Background music as (path, volume), started once the bot is not muted
+    #[cfg(feature = "audio")]
+    music: Option<(String, f32)>,
+    #[cfg(feature = "audio")]
+    music_playing: bool,
+    pickup_sounds: HashMap<Content, String>,
+    // Sounds played when the bot enters a state
+    #[cfg(feature = "audio")]
+    state_sounds: HashMap<State, OxAgSoundConfig>,
     pub search_tool: SearchTool,
+    rng: StdRng,
+    // Wandering ticks, used to re-plan every `replan_interval` of them
     pub timer: usize,
+    // Radius used by the search tool and ticks spent looking for a bank
+    search_radius: usize,
+    bank_search_ticks: usize,
+    ticks: usize,
+    // The world sent the Ready event
+    ready: bool,
+    // Set when the energy is too low to act, cleared by the recharge
+    waiting_for_energy: bool,
+    // Position and energy at the end of the last tick spent waiting for energy, None if the bot did something else
+    waiting_snapshot: Option<((usize, usize), usize)>,
+    // Why the bot did nothing in the last tick, None if it acted
+    idle_reason: Option<IdleReason>,
+    // Last content the world said was added to the backpack, and the destroys
+    // that did not add anything, as (content, destroyed quantity)
+    last_added: Option<(Content, usize)>,
+    missed_pickups: Vec<(Content, usize)>,
+    // Coin piles left behind because the backpack was full, as (coordinate, coins left)
+    pending_harvest: Vec<((usize, usize), usize)>,
+    // Energy spent by the actions of the bot
+    energy_spent: usize,
+    tick_started: Option<Instant>,
+    pub config: SaverConfig,
+
+    // Actions that failed during the last tick
+    errors: Vec<ActionError>,
+    // Tiles destroyed during the current tick
+    destroyed: HashSet<(usize, usize)>,
+    // Called after every action
+    step_callback: Option<Box<dyn FnMut()>>,
 
-    pub seen: Vec<((i32, i32), Tile)>
+    // Trace of the tick in progress and of the last one
+    current_trace: Option<TickTrace>,
+    last_trace: Option<TickTrace>,
+
+    // Position the bot is currently trying to reach
+    target: Option<(usize, usize)>,
+    // Position of the bot, kept in sync by the move events
+    position: (usize, usize),
+    // Steps done so far and the (estimated, actual) steps of the last trip to a bank
+    steps: usize,
+    last_travel: Option<(usize, usize)>,
+    // Rectangle (min, max) the bot has to stay in
+    region: Option<((usize, usize), (usize, usize))>,
+    // Area the user asked the bot to explore
+    explore_target: Option<(usize, usize)>,
+    // What the search tool found in the last wander
+    last_search_results: HashMap<Content, Vec<(usize, usize)>>,
+    // Where the bot started, and the waypoint of the exploration pattern it is going to
+    home: Option<(usize, usize)>,
+    // Asphalt projects of the enclosure already built, and the bank it goes around
+    asphalt_done: usize,
+    asphalt_bank: Option<(usize, usize)>,
+    pattern_step: usize,
+
+    pub seen: Vec<((usize, usize), Tile)>,
+    // Position in seen of every coordinate, and the seen grouped in square buckets
+    seen_index: HashMap<(usize, usize), usize>,
+    seen_buckets: HashMap<(usize, usize), Vec<usize>>
 }
 
 /// Initialized a new SaverBot, and you can ask for a goal
@@ -104,36 +266,10 @@ pub struct SaverBot{
 #[macro_export]
 macro_rules! new_saver_bot {
     ($x: expr) => {
-        SaverBot{
-            robot: Robot::new(),
-            state: State::CoinCollecting,
-            goal: None,
-            filled_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(), 
-            free_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(),
-            saved: 0,
-            looking_for: COIN_LOOKING_FOR.to_vec(),
-            audio: SaverBot::audio_init(), 
-            search_tool: SearchTool::new(),
-            used_banks: HashMap::new(),
-            timer: 0, 
-            seen: vec![]
-        }
+        $crate::SaverBot::new(None)
     };
     ($x:expr, $y: expr) => {
-        SaverBot{
-            robot: Robot::new(),
-            state: State::CoinCollecting,
-            goal: Some($x),
-            filled_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(), 
-            free_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(),
-            saved: 0,
-            looking_for: COIN_LOOKING_FOR.to_vec(),
-            audio: SaverBot::audio_init(),
-            search_tool: SearchTool::new(),
-            used_banks: HashMap::new(),
-            timer: 0, 
-            seen: vec![]
-        }
+        $crate::SaverBot::new(Some($x))
     };
 }
 
@@ -155,66 +291,54 @@ macro_rules! new_saver_bot {
 /// }
 impl Runnable for SaverBot {
     fn process_tick(&mut self, world: &mut World) {
-        // Debug print
-        println!("ROBOT");
-        println!("- STATE: {:?}", self.state);
-        println!("- POSITION: {:?}", self.robot.coordinate);
-        println!("- ENERGY: {:?}", self.robot.energy.get_energy_level());
-        println!("- BACKPACK: {:?}", self.robot.backpack);
-        println!("- SAVED: {:?}", self.saved);
+        self.ticks += 1;
+        self.idle_reason = None;
+        self.errors.clear();
+        self.destroyed.clear();
+        self.tick_started = Some(Instant::now());
 
-        // Utility functions, to do all the things that can be done 
-        // at the same time, regardless of what the robot is currently trying to do
-        self.look_for_unknown_banks(world); // 0 energy required
-        self.destroy_area(world); // Pay just if destroy something currently useful
+        // The teleports happen while moving, see `go_tracked`
+        self.sync_position();
 
-        // If enery to low, wait for recharge
-        if !self.get_energy().has_enough_energy(150)  {
-            return;
-        }  
+        if self.home.is_none() {
+            self.home = Some(self.current_position());
+        }
 
-        // Save the coordinates in the vector
-        let res = where_am_i(self, world);
-        match res {
-            (tiles, (x, y)) => {
-                for i in 0..3 {
-                    for j in 0..3 {
-                        if let Some(tile) = &tiles[i][j] {
-                            if !self.seen.contains(&(((x + i - 1) as i32, (y + j - 1) as i32), tile.clone())) {
-                                self.seen.push((((x + i - 1) as i32, (y + j - 1) as i32), tile.clone()));
-                            }
-                        }
-                    }
-                }
-            }
+        if self.config.trace {
+            self.current_trace = Some(TickTrace::new(self.ticks, self.state));
         }
 
-        match self.get_state() {
-            State::CoinCollecting => {
-                self.coin_collect(world);
-            }, 
-            State::RockCollecting => {
-                self.rock_collect(world);
-            },
-            State::Finish => {
-                self.finish(world);
-            },
-            State::Saving => {
-                self.save(world);
-            },
-            State::Enjoying => {
-                self.enjoy();
-            },
-            State::Trading => {
-                self.trade();
-            }, 
-            State::BankSearching => {
-                self.search_for_bank(world);
-            }
+        // Recharges happen between the ticks, so only what is spent during the tick is counted
+        let energy_before = self.energy_level();
+        if self.budget_exhausted() {
+            self.stop_for_budget();
+        } else {
+            self.run_tick(world);
+        }
+        self.energy_spent += energy_before.saturating_sub(self.energy_level());
+        if self.budget_exhausted() {
+            self.stop_for_budget();
         }
+        self.waiting_snapshot = match self.idle_reason {
+            Some(IdleReason::LowEnergy) => Some((self.current_position(), self.energy_level())),
+            _ => None
+        };
+        if let Some(trace) = self.current_trace.take() {
+            self.last_trace = Some(trace);
+        }
+        #[cfg(debug_assertions)]
+        self.check_invariants();
     }
     fn handle_event(&mut self, event: Event) {
-        let _ = self.audio.play_audio_based_on_event(&event);
+        match &event {
+            Event::Ready => self.ready = true,
+            // Act again from the next tick, without waiting for the energy gate
+            Event::EnergyRecharged(_) => self.waiting_for_energy = false,
+            Event::Moved(_, coord) => self.position = *coord,
+            Event::AddedToBackpack(content, quantity) => self.last_added = Some((content.to_default(), *quantity)),
+            _ => {}
+        }
+        self.play_event_sound(&event);
         println!("{:?}", event);
     }
     fn get_energy(&self) -> &Energy {
@@ -255,517 +379,4292 @@ impl SaverBot {
     pub fn new(goal: Option<usize>) -> Self {
         // Charting tool used here (an not only here)
         // Search tool used here (an not only here)
+        let config = SaverConfig::default();
         SaverBot{
             robot: Robot::new(),
             state: State::CoinCollecting,
             goal,
+            goal_set_tick: 0,
             filled_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(), 
             free_banks: ChartingTools::tool::<ChartedMap<Content>>().unwrap(),
             saved: 0,
+            deposited: HashMap::new(),
+            shutting_down: false,
+            last_trade_tick: None,
             looking_for: COIN_LOOKING_FOR.to_vec(),
-            audio: SaverBot::audio_init(),
+            #[cfg(feature = "audio")]
+            audio: None,
+            muted: false,
+            master_volume: 1.0,
+            #[cfg(feature = "audio")]
+            music: None,
+            #[cfg(feature = "audio")]
+            music_playing: false,
+            pickup_sounds: HashMap::new(),
+            #[cfg(feature = "audio")]
+            state_sounds: HashMap::new(),
             search_tool: SearchTool::new(),
+            rng: StdRng::from_entropy(),
             used_banks: HashMap::new(),
+            used_banks_tick: HashMap::new(),
+            bank_capacity: HashMap::new(),
+            bank_accepted: HashMap::new(),
+            bank_accepts: HashMap::new(),
+            put_failures: HashMap::new(),
+            on_bank_found: None,
+            reserved: HashSet::new(),
+            reservations: None,
+            bank_cooldowns: HashMap::new(),
             timer: 0, 
-            seen: vec![]
+            search_radius: config.search_radius,
+            bank_search_ticks: 0,
+            ticks: 0,
+            ready: false,
+            waiting_for_energy: false,
+            waiting_snapshot: None,
+            idle_reason: None,
+            last_added: None,
+            missed_pickups: vec![],
+            pending_harvest: vec![],
+            energy_spent: 0,
+            tick_started: None,
+            config,
+            errors: vec![],
+            destroyed: HashSet::new(),
+            step_callback: None,
+            current_trace: None,
+            last_trace: None,
+            target: None,
+            position: (0, 0),
+            steps: 0,
+            last_travel: None,
+            region: None,
+            explore_target: None,
+            last_search_results: HashMap::new(),
+            home: None,
+            asphalt_done: 0,
+            asphalt_bank: None,
+            pattern_step: 0,
+            seen: vec![],
+            seen_index: HashMap::new(),
+            seen_buckets: HashMap::new()
         }        
     }
-    fn set_state(&mut self, state: State) {
-        self.state = state;
-    }
-    fn get_state(&self) -> &State {
-        &self.state
+    /// Creates a bot that plays the events with the given audio tool instead of the default sounds
+    /// The volume and pickup sounds settings do not change a tool given this way
+    #[cfg(feature = "audio")]
+    pub fn with_audio(goal: Option<usize>, audio: OxAgAudioTool) -> Self {
+        let mut bot = SaverBot::new(goal);
+        bot.audio = Some(audio);
+        bot
     }
-
-    fn reach_position(&mut self, world: &mut World, x: usize, y: usize) -> bool {
-        println!("Reach position");
-        while self.get_coordinate().get_row() < x && self.get_energy().has_enough_energy(50) {
-            let _ = go(self, world, Direction::Down);
+    /// Creates a bot starting from the given state instead of CoinCollecting
+    /// Returns None if the bot can't start in that state
+    pub fn with_initial_state(goal: Option<usize>, state: State) -> Option<Self> {
+        if !state.can_start() {
+            println!("The bot can't start in {:?}", state);
+            return None;
         }
-        while self.get_coordinate().get_row() > x && self.get_energy().has_enough_energy(50) {
-            let _ = go(self, world, Direction::Up);
+        let mut bot = SaverBot::new(goal);
+        bot.state = state;
+        Some(bot)
+    }
+    fn set_state(&mut self, state: State) -> bool {
+        if !self.state.can_transition_to(&state) {
+            self.trace_decision(format!("Illegal transition from {:?} to {:?}", self.state, state));
+            println!("Illegal transition from {:?} to {:?}", self.state, state);
+            return false;
         }
-        while self.get_coordinate().get_col() < y && self.get_energy().has_enough_energy(50){
-            let _ = go(self, world,  Direction::Right);
+        if self.state != state {
+            self.trace_decision(format!("{:?} -> {:?}", self.state, state));
+            #[cfg(feature = "audio")]
+            if !self.muted && self.state_sounds.contains_key(&state) {
+                self.audio_tool();
+                if let (Some(audio), Some(sound)) = (self.audio.as_mut(), self.state_sounds.get(&state)) {
+                    let result = audio.play_audio(sound);
+                    self.trace_action(format!("sound of {:?}: {:?}", state, result.map(|_| ())));
+                }
+            }
         }
-        while self.get_coordinate().get_col() > y && self.get_energy().has_enough_energy(50){
-            let _ = go(self, world, Direction::Left);
+        self.state = state;
+        true
+    }
+    /// Trace of the last tick, if tracing is enabled in the config
+    pub fn last_trace(&self) -> Option<&TickTrace> {
+        self.last_trace.as_ref()
+    }
+    fn trace_decision(&mut self, decision: String) {
+        if let Some(trace) = self.current_trace.as_mut() {
+            trace.decisions.push(decision);
         }
-        self.get_coordinate().get_row() == x && self.get_coordinate().get_col() == y
     }
-
-    fn check_if_seen(&mut self, x: usize, y: usize) -> bool {
-        for ((x_seen, y_seen), _) in self.seen.iter() {
-            if x == *x_seen as usize && y == *y_seen as usize {
-                return true;
-            }
+    fn trace_action(&mut self, action: String) {
+        if let Some(trace) = self.current_trace.as_mut() {
+            trace.actions.push(action);
         }
-        false
     }
-    pub fn audio_init() -> OxAgAudioTool {
-        // Audio tool used here
-
-        // Configure events
-        let mut events = HashMap::new();
-        events.insert(Event::Ready, OxAgSoundConfig::new("assets/default/event/event_ready.ogg"));
-        for i in 0..15 {
-            events.insert(Event::AddedToBackpack(Content::Coin(0), i), OxAgSoundConfig::new("assets/default/event/event_add_to_backpack.ogg"));
-            events.insert(Event::AddedToBackpack(Content::Rock(0), i), OxAgSoundConfig::new("assets/default/event/event_add_to_backpack.ogg"));
-            events.insert(Event::AddedToBackpack(Content::Garbage(0), i), OxAgSoundConfig::new("assets/default/event/event_add_to_backpack.ogg"));
-            events.insert(Event::AddedToBackpack(Content::Tree(0), i), OxAgSoundConfig::new("assets/default/event/event_add_to_backpack.ogg"));
+    fn get_state(&self) -> &State {
+        &self.state
+    }
+    fn run_tick(&mut self, world: &mut World) {
+        // Still waiting for energy on the same tile since the last tick, with at most a recharge not
+        // reaching the gate: the banks and the neighborhood were already looked at, so nothing is done at all
+        if let Some((position, energy)) = self.waiting_snapshot {
+            if self.ready && position == self.current_position() && self.energy_level() >= energy
+                && !self.get_energy().has_enough_energy(self.energy_gate()) {
+                self.idle_reason = Some(IdleReason::LowEnergy);
+                self.trace_decision(String::from("Waiting for energy, nothing changed"));
+                return;
+            }
         }
-        events.insert(Event::EnergyRecharged(10), OxAgSoundConfig::new("assets/default/event/event_energy_recharged.ogg"));
-        events.insert(Event::Terminated, OxAgSoundConfig::new("assets/default/event/event_terminated.ogg"));
 
-        // Configure tiles
-        let mut tiles = HashMap::new();
-        tiles.insert(TileType::DeepWater, OxAgSoundConfig::new("assets/default/tile/tile_water.ogg"));
-        tiles.insert(TileType::ShallowWater, OxAgSoundConfig::new("assets/default/tile/tile_water.ogg"));
-        tiles.insert(TileType::Sand, OxAgSoundConfig::new("assets/default/tile/tile_sand.ogg"));
-        tiles.insert(TileType::Grass, OxAgSoundConfig::new("assets/default/tile/tile_grass.ogg"));
-        tiles.insert(TileType::Hill, OxAgSoundConfig::new("assets/default/tile/tile_grass.ogg"));
-        tiles.insert(TileType::Mountain, OxAgSoundConfig::new("assets/default/tile/tile_mountain.ogg"));
-        tiles.insert(TileType::Snow, OxAgSoundConfig::new("assets/default/tile/tile_snow.ogg"));
-        tiles.insert(TileType::Lava, OxAgSoundConfig::new("assets/default/tile/tile_lava.ogg"));
-        tiles.insert(TileType::Teleport(false), OxAgSoundConfig::new("assets/default/tile/tile_teleport.ogg"));
-        tiles.insert(TileType::Street, OxAgSoundConfig::new("assets/default/tile/tile_street.ogg"));
+        // Debug print
+        println!("{}", self.describe());
 
-        // Configure weather
-        let mut weather = HashMap::new();
-        weather.insert(WeatherType::Rainy, OxAgSoundConfig::new_looped_with_volume("assets/default/weather/weather_rainy.ogg", 0.4));
-        weather.insert(WeatherType::Sunny, OxAgSoundConfig::new_looped("assets/default/weather/weather_sunny.ogg"));
+        // Utility functions, to do all the things that can be done 
+        // at the same time, regardless of what the robot is currently trying to do
+        self.look_for_unknown_banks(world); // 0 energy required
 
-        // Initialize audio
-        let audio = OxAgAudioTool::new(events, tiles, weather);
-        match audio {
-            Ok(audio) => audio,
-            Err(error) => panic!("Error while initializing audio: {:?}", error)
+        // Before the world is ready only the bookkeeping is done
+        if !self.ready {
+            self.idle_reason = Some(IdleReason::NotReady);
+            self.trace_decision(String::from("Waiting for the world to be ready"));
+            self.record_neighborhood(world);
+            return;
         }
-        
-    }
-    fn trade(&mut self) {
-        // Recycle tool used here
-        let trade = recycle(self, 0);
-        match trade {
-            Ok(coins) => println!("You traded {} coins", coins),
-            Err(error) => println!("While trading there has been an issue {:?}", error)
+        // Nothing is spent until the energy is recharged
+        if self.waiting_for_energy && !self.get_energy().has_enough_energy(self.energy_gate()) {
+            self.idle_reason = Some(IdleReason::LowEnergy);
+            self.trace_decision(String::from("Waiting for energy"));
+            return;
         }
+        self.waiting_for_energy = false;
+        self.destroy_area(world); // Pay just if destroy something currently useful
 
-        let current_number_coins = self.get_backpack().get_contents().get(&Content::Coin(0)).unwrap();
-        if current_number_coins >= &12 {
-            self.set_state(State::Saving)
-        }else {
-            self.set_state(State::CoinCollecting)
-        }
-    }
-    fn coin_collect(&mut self, world: &mut World) {
-        println!("Coin collecting");
-        if self.goal.is_some() && self.goal.unwrap() <= self.saved + self.get_backpack().get_contents().get(&Content::Coin(0)).unwrap() {
-            self.set_state(State::Saving);
+        // If enery to low, wait for recharge
+        if !self.get_energy().has_enough_energy(self.energy_gate())  {
+            self.idle_reason = Some(IdleReason::LowEnergy);
+            self.trace_decision(String::from("Waiting for energy"));
+            self.waiting_for_energy = true;
+            return;
+        }  
+
+        self.record_neighborhood(world);
+
+        // With little energy the long activities wait for a weather that recharges faster
+        if self.waiting_for_weather(world) {
+            self.idle_reason = Some(IdleReason::Weather);
+            self.trace_decision(String::from("Waiting for a better weather"));
             return;
         }
-        self.wander_in_seach_of(world, COIN_LOOKING_FOR.to_vec());
-        
-        let current_number_coins = self.get_backpack().get_contents().get(&Content::Coin(0)).unwrap();
-        let current_number_garbage = self.get_backpack().get_contents().get(&Content::Garbage(0)).unwrap();
-        let current_number_rock = self.get_backpack().get_contents().get(&Content::Rock(0)).unwrap();
 
-        // Change state if too many coin to save or if there are enough to trade
-        if current_number_coins >= &12 {
-            self.set_state(State::Saving)
-        }else if (current_number_garbage >= &5) || (current_number_rock >= &3) {
-            self.set_state(State::Trading)
+        match self.get_state() {
+            State::CoinCollecting => {
+                self.coin_collect(world);
+            }, 
+            State::RockCollecting => {
+                self.rock_collect(world);
+            },
+            State::Finish => {
+                self.finish(world);
+            },
+            State::Saving => {
+                self.save(world);
+            },
+            State::Enjoying => {
+                self.enjoy();
+            },
+            State::Trading => {
+                self.trade();
+            }, 
+            State::BankSearching => {
+                self.search_for_bank(world);
+            }
         }
     }
-    fn destroy_area(&mut self, world: &mut World) {
-        // Destroy zone tool used here
-        let mut banks_points = vec![];
-        if let Some(banks) = self.free_banks.get(&Content::Bank(Range { start: 0, end:0 })) {
-            for bank in banks.iter() {
-                banks_points.push((bank.0.0, bank.0.1));
-            }
+    // Save the coordinates in the vector
+    fn record_neighborhood(&mut self, world: &mut World) {
+        let (tiles, position) = where_am_i(self, world);
+        for (coord, tile) in neighborhood_cells(&tiles, position) {
+            self.record_seen(coord, tile);
         }
-        if let Some(banks) = self.filled_banks.get(&Content::Bank(Range { start: 0, end:0 })) {
-            for bank in banks.iter() {
-                banks_points.push((bank.0.0, bank.0.1));
-            }
+    }
+    // Catches the inconsistent states left by a tick, only in debug builds
+    #[cfg(debug_assertions)]
+    fn check_invariants(&self) {
+        for bank in self.known_banks(BankStatus::Free) {
+            debug_assert!(!self.is_filled_bank(bank), "Bank {:?} is both free and filled", bank);
         }
-        let (x, y) = (self.get_coordinate().get_row() as i32, self.get_coordinate().get_col() as i32);
-        let directs = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 0), (0, 1), (1, -1), (1, 0), (1, -1)];
-        let mut good = true;
-        for (ox, oy) in directs.iter() {
-            let (big_x, big_y) = (x + ox, y + oy);
-            if banks_points.contains(&(big_x as usize, big_y as usize)) {
-                good = false;
-            }
+        debug_assert!(self.seen.len() == self.seen_index.len(), "Seen has {} tiles but {} indexed", self.seen.len(), self.seen_index.len());
+        debug_assert!(self.asphalt_done <= ASPHALT_PROJECTS, "{} asphalt projects built out of {}", self.asphalt_done, ASPHALT_PROJECTS);
+        debug_assert!(
+            self.saved <= self.deposited.get(&Content::Coin(0)).cloned().unwrap_or(0),
+            "Saved {} coins but deposited {:?}", self.saved, self.deposited.get(&Content::Coin(0))
+        );
+        if self.shutting_down && self.state == State::Enjoying {
+            debug_assert!(self.bankable_count() == 0, "Enjoying the shutdown while still carrying {:?}", self.config.bank_contents);
         }
-        if good {
-            let needs = self.looking_for.clone();
-            for content in needs.iter() {
-                let _ = DestroyZone.execute(world, self, content.clone());
-            }
-        } else {
-            let (tiles, _) = where_am_i(self, world);
-            for i in 0..3 {
-                for j in 0..3 {
-                    let (cx, cy) = (x + i - 1, y + j - 1);
-                    let tile = &tiles[i as usize][j as usize];
-                    match tile {
-                        None => {},
-                        Some(tile) => {
-                            let content = tile.content.clone();
-                            if self.looking_for.contains(&content) && content != Content::Bank(Range { start: 0, end: 0 }) {
-                                let direction = if cx > x {Direction::Down} else if cx < x {Direction::Up} else if cy > y {Direction::Right} else {Direction::Left};
-                                let thing = destroy(self, world, direction);
-                                match thing {
-                                    Ok(number) => {println!("Destroyed {} {:?}", number, content);},
-                                    Err(error) => println!("While destroying there has been an issue {:?}", error)
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
+    }
+    // Energy needed for the current state to act
+    fn energy_gate(&self) -> usize {
+        self.config.state_energy.get(&self.state).cloned().unwrap_or(self.config.min_energy)
+    }
+    // Tells if the bot should wait for the recharge weather before going on
+    fn waiting_for_weather(&self, world: &World) -> bool {
+        if !self.config.wait_for_recharge_weather || self.get_energy().has_enough_energy(self.config.low_energy) {
+            return false;
         }
-        
+        let weather = look_at_sky(world).get_weather_condition();
+        !self.config.recharge_weathers.contains(&weather)
     }
-    fn rock_collect(&mut self, world: &mut World) {
-        println!("Rock collecting");
-        // remove all coins from the backpack
-        let _ = put(self, world, Content::Coin(0), self.get_backpack().get_contents().get(&Content::Coin(0)).unwrap().clone(), Direction::Up);
-        // remove all garbage from the backpack
-        let _ = put(self, world, Content::Garbage(0), self.get_backpack().get_contents().get(&Content::Garbage(0)).unwrap().clone(), Direction::Up);
-        self.wander_in_seach_of(world, ROCK_LOOKING_FOR.to_vec());
-        let current_number_rock = self.get_backpack().get_contents().get(&Content::Rock(0)).unwrap();
-        println!("CURRENT number of rock: {:?}", current_number_rock);
-        // Change state if enough rock
-        if current_number_rock >= &8 {
-            self.set_state(State::Finish)
+    // Goes to the configured fallback state when the current one has nothing sensible to do
+    fn fall_back(&mut self, reason: &str) {
+        let fallback = self.config.fallback_state;
+        println!("{}, falling back to {:?}", reason, fallback);
+        if self.state.can_transition_to(&fallback) {
+            self.set_state(fallback);
+        } else {
+            self.force_state(fallback);
         }
     }
-    fn enjoy(&mut self) {
-        // Does nothing
-        println!("Enjoying");
+    // Used by the public API to jump to a state regardless of the transitions table
+    fn force_state(&mut self, state: State) {
+        println!("Forced transition from {:?} to {:?}", self.state, state);
+        self.state = state;
     }
-    fn search_for_bank(&mut self, world: &mut World) {
-        println!("Searching for bank");
-        if self.free_banks.get(&Content::Bank(Range { start: 0, end: 0 })).iter().len() > 0 {
+
+    /// Stops the bot: the coins in the backpack are brought to the closest bank,
+    /// then the bot goes enjoying. Use `is_done` to know when it finished
+    pub fn begin_shutdown(&mut self) {
+        self.shutting_down = true;
+        if self.bankable_count() > 0 {
             self.set_state(State::Saving);
         } else {
-            self.look_for_unknown_banks(world);
-            self.wander_in_seach_of(world, BANK_LOOKING_FOR.to_vec());
+            self.set_state(State::Enjoying);
         }
     }
-    fn go_to_closest_open_bank(&mut self, world: &mut World) -> Option<Direction> {
-        let know_bank = self.free_banks.iter().len() > 0;
-        if know_bank {
-            let (x, y) = self.closest_bank();
-            println!("Closest bank is at {:?} {:?}", x, y);
-            self.reach_position(world, x, y);
-        } else {
-            self.wander_in_seach_of(world, BANK_LOOKING_FOR.to_vec());
+    /// Brings the bot back to the initial state with a new goal,
+    /// the audio and search tools are kept as they are
+    pub fn reset(&mut self, goal: Option<usize>) {
+        self.force_state(State::CoinCollecting);
+        self.goal = goal;
+        self.goal_set_tick = 0;
+        self.clear_banks();
+        self.used_banks.clear();
+        self.used_banks_tick.clear();
+        self.bank_capacity.clear();
+        self.bank_accepted.clear();
+        self.bank_accepts.clear();
+        self.put_failures.clear();
+        for coord in self.reserved.clone() {
+            self.release_bank(coord);
         }
-
-        let (neighborhoods, (rx, ry)) = where_am_i(self, &world);
-        for x in 0..3 {
-            for y in 0..3 {
-                let tile = &neighborhoods[x][y];
-                if let Some(tile) = tile {
-                    match &tile.content.to_default() {
-                        Content::Bank(_) => {
-                            let dir = if rx + 1 == x {Direction::Up} else if rx - 1 == x {Direction::Down} else if ry + 1 == y {Direction::Left} else {Direction::Right};
-                            return Some(dir);
-                        }
-                        _ => {}
-                    }
-                }
+        self.bank_cooldowns.clear();
+        self.saved = 0;
+        self.deposited.clear();
+        self.shutting_down = false;
+        self.last_trade_tick = None;
+        self.looking_for = COIN_LOOKING_FOR.to_vec();
+        self.timer = 0;
+        self.search_radius = self.config.search_radius;
+        self.bank_search_ticks = 0;
+        self.ticks = 0;
+        self.tick_started = None;
+        self.waiting_for_energy = false;
+        self.waiting_snapshot = None;
+        self.idle_reason = None;
+        self.last_added = None;
+        self.missed_pickups.clear();
+        self.pending_harvest.clear();
+        self.energy_spent = 0;
+        self.errors.clear();
+        self.destroyed.clear();
+        self.current_trace = None;
+        self.last_trace = None;
+        self.target = None;
+        self.sync_position();
+        self.steps = 0;
+        self.last_travel = None;
+        self.explore_target = None;
+        self.last_search_results.clear();
+        self.home = None;
+        self.asphalt_done = 0;
+        self.asphalt_bank = None;
+        self.pattern_step = 0;
+        self.seen.clear();
+        self.seen_index.clear();
+        self.seen_buckets.clear();
+    }
+    fn clear_banks(&mut self) {
+        let mut free = vec![];
+        if let Some(banks) = self.free_banks.get(&bank_key()) {
+            for (bank, _) in banks.iter() {
+                free.push((bank.0, bank.1));
             }
         }
-        None
-    }
-    fn look_for_unknown_banks(&mut self, world: &mut World) {
-        let (neighborhoods, (x, y)) = where_am_i(self, &world);
-
-        let current_banks = self.free_banks.iter();
-        let mut seend_coord: Vec<(usize, usize)> = vec![];
-        for (_, coord) in current_banks {
-            for (pos, _) in coord {
-                seend_coord.push((pos.0, pos.1));
+        for (x, y) in free {
+            let _ = self.free_banks.remove(&bank_key(), ChartedCoordinate(x, y));
+        }
+        let mut filled = vec![];
+        if let Some(banks) = self.filled_banks.get(&bank_key()) {
+            for (bank, _) in banks.iter() {
+                filled.push((bank.0, bank.1));
             }
         }
-
-        // Searching if nearby there is a bank in the range
-        for i in 0..3 {
-            for j in 0..3 {
-                let tile = &neighborhoods[i][j];
-                if let Some(tile) = tile {
-                    match &tile.content.to_default() {
-                        Content::Bank(_) => {
-                            if !seend_coord.contains(&(x + i - 1, y + j - 1)) {
-                                self.free_banks.save(&tile.content.to_default(), &ChartedCoordinate(x + i - 1, y + j - 1));
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                
+        for (x, y) in filled {
+            let _ = self.filled_banks.remove(&bank_key(), ChartedCoordinate(x, y));
+        }
+    }
+    /// Re-plans every `interval` wandering ticks, 0 to never re-plan
+    pub fn set_replan_interval(&mut self, interval: usize) {
+        self.config.replan_interval = interval;
+    }
+    // Forgets the banks that are not there anymore and the target computed from old data
+    fn replan(&mut self) {
+        println!("Re-planning after {} wandering ticks", self.timer);
+        let mut known = self.known_banks(BankStatus::Free);
+        known.extend(self.known_banks(BankStatus::Filled));
+        for coord in known {
+            let still_bank = match self.seen_index.get(&coord) {
+                Some(index) => matches!(self.seen[*index].1.content, Content::Bank(_)),
+                None => true
+            };
+            if !still_bank {
+                println!("The bank at {:?} is gone", coord);
+                let _ = self.free_banks.remove(&bank_key(), ChartedCoordinate(coord.0, coord.1));
+                let _ = self.filled_banks.remove(&bank_key(), ChartedCoordinate(coord.0, coord.1));
+                self.bank_capacity.remove(&coord);
+                self.used_banks.remove(&coord);
+                self.used_banks_tick.remove(&coord);
+                self.release_bank(coord);
             }
         }
+        self.target = None;
+    }
+    /// Tells if the shutdown started with `begin_shutdown` is over
+    pub fn is_done(&self) -> bool {
+        self.shutting_down && self.state == State::Enjoying
     }
-    fn wander_in_seach_of(&mut self, world: &mut World, contents: Vec<Content>) {
-        self.destroy_area(world);
-
-        // Look if something interesting nearby with the tool 
-        let mut st = SearchTool::new();
-        
-        self.timer += 1;
-        let mut where_can_i_go = vec![];
-        let (x, y) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
 
-        for direction in DIRECTIONS.iter() {
-            match direction {
-                SearchDirection::BottomLeft => {
-                    let (cx, cy) = (x + 2, y - 2);
-                    if !self.check_if_seen(cx, cy) {
-                        where_can_i_go.push(SearchDirection::BottomLeft);
-                    }
-                },
-                SearchDirection::BottomRight => {
-                    let (cx, cy) = (x + 2, y + 2);
-                    if !self.check_if_seen(cx, cy) {
-                        where_can_i_go.push(SearchDirection::BottomRight);
-                    }
+    /// Limits the bot to the rectangle between min and max (both included),
+    /// it won't move, collect or destroy outside of it
+    pub fn set_region(&mut self, min: (usize, usize), max: (usize, usize)) {
+        self.region = Some(((min.0.min(max.0), min.1.min(max.1)), (min.0.max(max.0), min.1.max(max.1))));
+    }
+    /// Removes the limits set with `set_region`
+    pub fn clear_region(&mut self) {
+        self.region = None;
+    }
+    fn in_region(&self, coord: (usize, usize)) -> bool {
+        match self.region {
+            Some((min, max)) => coord.0 >= min.0 && coord.0 <= max.0 && coord.1 >= min.1 && coord.1 <= max.1,
+            None => true
+        }
+    }
+    fn clamp_to_region(&self, coord: (usize, usize)) -> (usize, usize) {
+        match self.region {
+            Some((min, max)) => (coord.0.clamp(min.0, max.0), coord.1.clamp(min.1, max.1)),
+            None => coord
+        }
+    }
+    /// Current position of the bot as (row, col)
+    pub fn current_position(&self) -> (usize, usize) {
+        self.position
+    }
+    // Reads the position again from the robot coordinate
+    fn sync_position(&mut self) {
+        self.position = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
+    }
+    /// Why the bot did nothing in the last tick, None if it acted
+    pub fn idle_reason(&self) -> Option<IdleReason> {
+        self.idle_reason
+    }
+    /// Energy spent since the start, recharges not counted
+    pub fn total_energy_spent(&self) -> usize {
+        self.energy_spent
+    }
+    // Tells if the bot spent all the energy the config gives it
+    fn budget_exhausted(&self) -> bool {
+        match self.config.energy_budget {
+            Some(budget) => self.energy_spent >= budget,
+            None => false
+        }
+    }
+    // Once the budget is over the bot does nothing anymore
+    fn stop_for_budget(&mut self) {
+        if self.state != State::Enjoying {
+            println!("Energy budget over after spending {}", self.energy_spent);
+            self.set_state(State::Enjoying);
+        }
+        self.idle_reason = Some(IdleReason::Done);
+    }
+    /// Runs a single tick on the world, as the runner would do once the world is ready,
+    /// so the bot can be driven without a `Runner`
+    pub fn step(&mut self, world: &mut World) {
+        // Nobody else tells the bot that the world is ready
+        self.ready = true;
+        self.process_tick(world);
+    }
+    /// Current energy of the bot
+    ///
+    /// # Examples
+    /// ```
+    /// use saver_bot::new_saver_bot;
+    ///
+    /// use robotics_lib::runner::Runnable;
+    ///
+    /// fn main () {
+    ///   let bot = new_saver_bot!(1);
+    ///   assert_eq!(bot.energy_level(), bot.get_energy().get_energy_level());
+    /// }
+    /// ```
+    pub fn energy_level(&self) -> usize {
+        self.get_energy().get_energy_level()
+    }
+    /// Coins saved in the banks so far
+    pub fn saved_count(&self) -> usize {
+        self.saved
+    }
+    /// Version of the bot and versions of the tools required by its manifest
+    pub fn version_info(&self) -> ToolVersions {
+        ToolVersions::required()
+    }
+    /// Coins saved per tick since the start
+    pub fn throughput(&self) -> f64 {
+        if self.ticks == 0 {
+            return 0.0;
+        }
+        self.saved as f64 / self.ticks as f64
+    }
+    /// State the bot is in
+    pub fn current_state(&self) -> State {
+        self.state
+    }
+    /// Actions that failed during the last tick
+    pub fn last_tick_errors(&self) -> &[ActionError] {
+        &self.errors
+    }
+    /// Sets a function called after every go, put and destroy of the bot,
+    /// useful to render the world between the actions of a single tick
+    pub fn set_step_callback(&mut self, callback: impl FnMut() + 'static) {
+        self.step_callback = Some(Box::new(callback));
+    }
+    // Called after every action, the description is built only when tracing
+    fn after_action(&mut self, action: impl FnOnce() -> String) {
+        if self.current_trace.is_some() {
+            self.trace_action(action());
+        }
+        if let Some(callback) = self.step_callback.as_mut() {
+            callback();
+        }
+    }
+    // Tells if the time given to the current tick is over
+    fn out_of_time(&self) -> bool {
+        match (self.config.max_tick_duration, self.tick_started) {
+            (Some(budget), Some(started)) => started.elapsed() >= budget,
+            _ => false
+        }
+    }
+    // The four tiles next to the bot (up, down, left, right) with their direction,
+    // None for the ones outside the map. The bot doesn't move
+    fn scan_adjacent(&self, world: &World) -> [Option<(Direction, Tile)>; 4] {
+        let (tiles, position) = where_am_i(self, world);
+        let (row, col) = neighborhood_center(&tiles, position);
+        let tile_at = |row: Option<usize>, col: Option<usize>| -> Option<Tile> {
+            tiles.get(row?)?.get(col?)?.clone()
+        };
+        [
+            tile_at(row.checked_sub(1), Some(col)).map(|tile| (Direction::Up, tile)),
+            tile_at(Some(row + 1), Some(col)).map(|tile| (Direction::Down, tile)),
+            tile_at(Some(row), col.checked_sub(1)).map(|tile| (Direction::Left, tile)),
+            tile_at(Some(row), Some(col + 1)).map(|tile| (Direction::Right, tile)),
+        ]
+    }
+    // Tile next to the bot in the direction, if inside the map
+    fn tile_in_direction(&self, world: &World, direction: &Direction) -> Option<Tile> {
+        let (tiles, position) = where_am_i(self, world);
+        let (row, col) = neighborhood_center(&tiles, position);
+        let (row, col) = match direction {
+            Direction::Up => (row.checked_sub(1)?, col),
+            Direction::Down => (row + 1, col),
+            Direction::Left => (row, col.checked_sub(1)?),
+            Direction::Right => (row, col + 1)
+        };
+        tiles.get(row)?.get(col)?.clone()
+    }
+    // Tells if the tile next to the bot in the direction holds a bank
+    fn bank_in_direction(&self, world: &World, direction: &Direction) -> bool {
+        matches!(self.tile_in_direction(world, direction).map(|tile| tile.content.to_default()), Some(Content::Bank(_)))
+    }
+    /// Drops up to `amount` of the content on the empty tiles around the bot, never in a bank
+    /// Returns how much was actually dropped
+    pub fn drop_content(&mut self, world: &mut World, content: Content, amount: usize) -> usize {
+        let mut dropped = 0;
+        for direction in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            let left = amount.saturating_sub(dropped).min(self.count_content(&content));
+            if left == 0 {
+                break;
+            }
+            let free = match self.tile_in_direction(world, &direction) {
+                Some(tile) => tile.content == Content::None && is_walkable(&tile.tile_type),
+                None => false
+            };
+            if !free {
+                continue;
+            }
+            if let Some(quantity) = self.try_put(world, content.to_default(), left, direction) {
+                dropped += quantity;
+            }
+        }
+        if dropped > 0 {
+            println!("Dropped {} {:?}", dropped, content);
+        }
+        dropped
+    }
+    // Moves of one tile, keeping track of the failure
+    fn try_go(&mut self, world: &mut World, direction: Direction) -> bool {
+        if let Some(next) = step_from(self.current_position(), &direction) {
+            if !self.in_region(next) {
+                println!("Refusing to leave the region going {:?}", direction);
+                self.errors.push(ActionError::OutOfRegion(direction));
+                return false;
+            }
+        }
+        if self.config.avoid_lava {
+            if let Some(tile) = self.tile_in_direction(world, &direction) {
+                if tile.tile_type == TileType::Lava {
+                    println!("Refusing to step on lava going {:?}", direction);
+                    self.errors.push(ActionError::Unsafe(direction, tile.tile_type));
+                    return false;
+                }
+            }
+        }
+        let result = go(self, world, direction.clone());
+        // In case the move event did not arrive
+        self.sync_position();
+        self.after_action(|| format!("go {:?}: {:?}", direction, result.as_ref().map(|_| ())));
+        match result {
+            Ok(_) => {
+                self.steps += 1;
+                // Keep the map up to date with what is met along the way
+                self.record_neighborhood(world);
+                true
+            },
+            Err(error) => {
+                self.errors.push(ActionError::Go(direction, error));
+                false
+            }
+        }
+    }
+    // Puts the content in the direction, keeping track of the failure
+    fn try_put(&mut self, world: &mut World, content: Content, quantity: usize, direction: Direction) -> Option<usize> {
+        let result = put(self, world, content.clone(), quantity, direction.clone());
+        self.after_action(|| format!("put {} {:?} {:?}: {:?}", quantity, content, direction, result));
+        match result {
+            Ok(quantity) => Some(quantity),
+            Err(error) => {
+                self.errors.push(ActionError::Put(content, direction, error));
+                None
+            }
+        }
+    }
+    // Moves of one tile, returns false if the bot didn't move or was teleported away
+    fn go_tracked(&mut self, world: &mut World, direction: Direction) -> bool {
+        let before = self.current_position();
+        self.try_go(world, direction);
+        let moved = manhattan(before, self.current_position());
+        if moved > 1 {
+            println!("Teleported from {:?} to {:?}", before, self.current_position());
+            self.target = None;
+        }
+        moved == 1
+    }
+    fn reach_position(&mut self, world: &mut World, x: usize, y: usize) -> bool {
+        println!("Reach position");
+        let (x, y) = self.clamp_to_region((x, y));
+        self.target = Some((x, y));
+        // Rows first, unless the bot went around an obstacle met moving along the row
+        let mut rows_first = true;
+        let mut detours = 0;
+        while self.current_position() != (x, y) && self.get_energy().has_enough_energy(self.config.per_step_energy) {
+            if self.out_of_time() {
+                break;
+            }
+            let (row, col) = self.current_position();
+            let vertical = if row < x { Some(Direction::Down) } else if row > x { Some(Direction::Up) } else { None };
+            let horizontal = if col < y { Some(Direction::Right) } else if col > y { Some(Direction::Left) } else { None };
+            let direction = match if rows_first { vertical.or(horizontal) } else { horizontal.or(vertical) } {
+                Some(direction) => direction,
+                None => break
+            };
+            if self.go_tracked(world, direction.clone()) {
+                self.collect_en_route(world, (x, y));
+                continue;
+            }
+            // Teleported, or next to a target that can't be walked on
+            if self.target.is_none() || manhattan((row, col), (x, y)) <= 1 || detours >= self.config.max_detours {
+                break;
+            }
+            detours += 1;
+            if !self.sidestep(world, &direction, (x, y)) {
+                break;
+            }
+            rows_first = matches!(direction, Direction::Up | Direction::Down);
+        }
+        if self.target.is_none() {
+            // Teleported while moving, the target has to be computed again
+            return false;
+        }
+        let reached = self.current_position() == (x, y);
+        if reached {
+            self.target = None;
+        }
+        reached
+    }
+
+    // Steps aside of the blocked direction, on the side of the target if any,
+    // so that the bot can go around the obstacle
+    fn sidestep(&mut self, world: &mut World, blocked: &Direction, target: (usize, usize)) -> bool {
+        let (row, col) = self.current_position();
+        let sides = match blocked {
+            Direction::Up | Direction::Down => if col > target.1 { [Direction::Left, Direction::Right] } else { [Direction::Right, Direction::Left] },
+            Direction::Left | Direction::Right => if row > target.0 { [Direction::Up, Direction::Down] } else { [Direction::Down, Direction::Up] },
+        };
+        sides.into_iter().any(|side| self.go_tracked(world, side))
+    }
+
+    // Destroys what is around while travelling, keeping the energy needed to get to the target
+    fn collect_en_route(&mut self, world: &mut World, target: (usize, usize)) {
+        if !self.config.collect_en_route {
+            return;
+        }
+        let needed = self.config.per_step_energy * (manhattan(self.current_position(), target) + 2);
+        if self.get_energy().has_enough_energy(needed) {
+            self.destroy_area(world);
+        }
+    }
+
+    // Directions where a step brings an unseen tile in view, the neighbors are always seen
+    // so the tile two steps away is looked at, the ones out of the world or the region are skipped
+    fn unseen_directions(&self, world: &World) -> Vec<Direction> {
+        let size = robot_map(world).map_or(0, |map| map.len());
+        let position = self.current_position();
+        [Direction::Up, Direction::Down, Direction::Left, Direction::Right].into_iter()
+            .filter(|direction| {
+                match step_from(position, direction).and_then(|next| step_from(next, direction)) {
+                    Some(border) => border.0 < size && border.1 < size && self.in_region(border) && !self.seen_index.contains_key(&border),
+                    None => false
+                }
+            })
+            .collect()
+    }
+
+    fn check_if_seen(&mut self, x: usize, y: usize) -> bool {
+        self.seen_index.contains_key(&(x, y))
+    }
+    // A quadrant is worth exploring if its corner exists and was never seen
+    fn unseen_quadrant(&mut self, x: Option<usize>, y: Option<usize>) -> bool {
+        match (x, y) {
+            (Some(x), Some(y)) => !self.check_if_seen(x, y),
+            _ => false
+        }
+    }
+    // Saves the tile in seen, replacing what was known of that coordinate
+    fn record_seen(&mut self, coord: (usize, usize), tile: Tile) {
+        match self.seen_index.get(&coord) {
+            Some(index) => self.seen[*index].1 = tile,
+            None => {
+                self.seen_index.insert(coord, self.seen.len());
+                self.seen_buckets.entry(seen_bucket(coord)).or_insert(vec![]).push(self.seen.len());
+                self.seen.push((coord, tile));
+            }
+        }
+    }
+    /// Fraction of the world (rows, cols) that the bot has seen
+    pub fn coverage(&self, world_dims: (usize, usize)) -> f64 {
+        let total = world_dims.0 * world_dims.1;
+        if total == 0 {
+            return 0.0;
+        }
+        let seen = self.seen.iter()
+            .filter(|((x, y), _)| *x < world_dims.0 && *y < world_dims.1)
+            .count();
+        seen as f64 / total as f64
+    }
+    /// All the tiles seen so far with their coordinate, without copying them
+    pub fn seen_iter(&self) -> impl Iterator<Item = (&(usize, usize), &Tile)> {
+        self.seen.iter().map(|(coord, tile)| (coord, tile))
+    }
+    /// Smallest and biggest (row, col) seen so far, None if nothing was seen
+    pub fn explored_bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let mut bounds: Option<((usize, usize), (usize, usize))> = None;
+        for ((x, y), _) in self.seen.iter() {
+            bounds = Some(match bounds {
+                Some((min, max)) => ((min.0.min(*x), min.1.min(*y)), (max.0.max(*x), max.1.max(*y))),
+                None => ((*x, *y), (*x, *y))
+            });
+        }
+        bounds
+    }
+    /// Grid of the seen tile types, from the smallest to the biggest seen coordinate
+    /// Unseen cells are None, the grid is empty if nothing was seen
+    pub fn export_map(&self) -> Vec<Vec<Option<TileType>>> {
+        let ((min_x, min_y), (max_x, max_y)) = match self.explored_bounds() {
+            Some(bounds) => bounds,
+            None => return vec![],
+        };
+        let mut map = vec![vec![None; max_y - min_y + 1]; max_x - min_x + 1];
+        for ((x, y), tile) in self.seen.iter() {
+            map[x - min_x][y - min_y] = Some(tile.tile_type.clone());
+        }
+        map
+    }
+    /// Closest seen tile holding the content (of any quantity)
+    pub fn nearest_known(&self, content: &Content) -> Option<(usize, usize)> {
+        let here = self.current_position();
+        self.seen.iter()
+            .filter(|(_, tile)| tile.content.to_default() == content.to_default())
+            .map(|(coord, _)| *coord)
+            .min_by_key(|coord| (manhattan(here, *coord), *coord))
+    }
+    /// Goes next to the closest seen tile holding the content
+    /// Returns true if the bot arrived
+    pub fn go_to_nearest(&mut self, world: &mut World, content: Content) -> bool {
+        match self.nearest_known(&content) {
+            Some(target) => self.go_next_to(world, target),
+            None => false,
+        }
+    }
+    // Goes next to the target, returns true if the bot arrived
+    fn go_next_to(&mut self, world: &mut World, target: (usize, usize)) -> bool {
+        let here = self.current_position();
+        if manhattan(here, target) <= 1 {
+            return true;
+        }
+        // Stop on the side of the tile facing the bot
+        let approach = direction_toward(target, here)
+            .and_then(|direction| step_from(target, &direction))
+            .unwrap_or(target);
+        self.reach_position(world, approach.0, approach.1)
+    }
+    /// Seen tiles within Manhattan distance `radius` from `center` that match the filter
+    pub fn seen_in_radius(&self, center: (usize, usize), radius: usize, filter: impl Fn(&Tile) -> bool) -> Vec<((usize, usize), Tile)> {
+        let (min_bucket, max_bucket) = (
+            seen_bucket((center.0.saturating_sub(radius), center.1.saturating_sub(radius))),
+            seen_bucket((center.0 + radius, center.1 + radius))
+        );
+        let mut found = vec![];
+        for bx in min_bucket.0..=max_bucket.0 {
+            for by in min_bucket.1..=max_bucket.1 {
+                if let Some(indexes) = self.seen_buckets.get(&(bx, by)) {
+                    for index in indexes {
+                        let ((x, y), tile) = &self.seen[*index];
+                        if manhattan((*x, *y), center) <= radius && filter(tile) {
+                            found.push(((*x, *y), tile.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+    /// Changes the volume of all the events, tiles and weather sounds
+    /// The audio tool can't change volume while running, so this is used when the tool is created
+    /// at the first sound: set it before running the bot. The music takes it when it starts
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.max(0.0);
+    }
+    /// Plays a different sound when the content is added to the backpack,
+    /// the contents without a sound of their own use the default one
+    /// As the volume, it is used when the audio tool is created
+    pub fn set_pickup_sound(&mut self, content: Content, path: &str) {
+        self.pickup_sounds.insert(content.to_default(), path.to_string());
+    }
+    /// Plays the sound every time the bot enters the state
+    #[cfg(feature = "audio")]
+    pub fn set_state_change_sound(&mut self, state: State, sound: OxAgSoundConfig) {
+        self.state_sounds.insert(state, sound);
+    }
+    // Audio tool of the bot, created with the current settings the first time it is needed
+    #[cfg(feature = "audio")]
+    fn audio_tool(&mut self) -> &mut OxAgAudioTool {
+        let (volume, pickup_sounds) = (self.master_volume, &self.pickup_sounds);
+        self.audio.get_or_insert_with(|| SaverBot::audio_init_with_sounds(volume, pickup_sounds))
+    }
+    /// Mutes (or unmutes) all the sounds played by the bot: the events, so the tiles and the weather
+    /// that come with them, the state changes and the music
+    /// The music waits to be unmuted to start, a loop already playing goes on
+    pub fn mute(&mut self, on: bool) {
+        if self.muted == on {
+            return;
+        }
+        self.muted = on;
+        #[cfg(feature = "audio")]
+        if let Err(error) = self.start_music() {
+            println!("While playing the music there has been an issue {:?}", error);
+        }
+    }
+    // Plays the sound of the event, returns false if it was skipped
+    fn play_event_sound(&mut self, event: &Event) -> bool {
+        #[cfg(feature = "audio")]
+        if !self.muted {
+            let _ = self.audio_tool().play_audio_based_on_event(event);
+            return true;
+        }
+        let _ = event;
+        false
+    }
+    /// Plays the background music in loop, scaled by the master volume
+    /// Muted, the music starts when the bot is unmuted
+    #[cfg(feature = "audio")]
+    pub fn play_music(&mut self, path: &str, volume: f32) -> Result<(), OxAgAudioToolError> {
+        self.music = Some((path.to_string(), volume));
+        self.music_playing = false;
+        self.start_music()
+    }
+    // Starts the music if there is one, not playing yet and the bot is not muted
+    #[cfg(feature = "audio")]
+    fn start_music(&mut self) -> Result<(), OxAgAudioToolError> {
+        if self.muted || self.music_playing {
+            return Ok(());
+        }
+        let music = match &self.music {
+            Some((path, volume)) => OxAgSoundConfig::new_looped_with_volume(path.as_str(), volume * self.master_volume),
+            None => return Ok(())
+        };
+        self.audio_tool().play_audio(&music)?;
+        self.music_playing = true;
+        Ok(())
+    }
+    #[cfg(feature = "audio")]
+    pub fn audio_init() -> OxAgAudioTool {
+        SaverBot::audio_init_with_volume(1.0)
+    }
+    #[cfg(feature = "audio")]
+    pub fn audio_init_with_volume(volume: f32) -> OxAgAudioTool {
+        SaverBot::audio_init_with_sounds(volume, &HashMap::new())
+    }
+    /// Sounds played for the events, with the pickup sounds chosen per content
+    #[cfg(feature = "audio")]
+    pub fn audio_events(volume: f32, pickup_sounds: &HashMap<Content, String>) -> HashMap<Event, OxAgSoundConfig> {
+        let mut events = HashMap::new();
+        events.insert(Event::Ready, OxAgSoundConfig::new_with_volume("assets/default/event/event_ready.ogg", volume));
+        let mut contents = vec![Content::Coin(0), Content::Rock(0), Content::Garbage(0), Content::Tree(0)];
+        for content in pickup_sounds.keys() {
+            if !contents.contains(content) {
+                contents.push(content.clone());
+            }
+        }
+        for i in 0..15 {
+            for content in contents.iter() {
+                let path = pickup_sounds.get(content).map(|path| path.as_str()).unwrap_or("assets/default/event/event_add_to_backpack.ogg");
+                events.insert(Event::AddedToBackpack(content.clone(), i), OxAgSoundConfig::new_with_volume(path, volume));
+            }
+        }
+        events.insert(Event::EnergyRecharged(10), OxAgSoundConfig::new_with_volume("assets/default/event/event_energy_recharged.ogg", volume));
+        events.insert(Event::Terminated, OxAgSoundConfig::new_with_volume("assets/default/event/event_terminated.ogg", volume));
+        events
+    }
+    #[cfg(feature = "audio")]
+    pub fn audio_init_with_sounds(volume: f32, pickup_sounds: &HashMap<Content, String>) -> OxAgAudioTool {
+        // Audio tool used here
+
+        // Configure events
+        let events = SaverBot::audio_events(volume, pickup_sounds);
+
+        // Configure tiles
+        let mut tiles = HashMap::new();
+        tiles.insert(TileType::DeepWater, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_water.ogg", volume));
+        tiles.insert(TileType::ShallowWater, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_water.ogg", volume));
+        tiles.insert(TileType::Sand, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_sand.ogg", volume));
+        tiles.insert(TileType::Grass, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_grass.ogg", volume));
+        tiles.insert(TileType::Hill, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_grass.ogg", volume));
+        tiles.insert(TileType::Mountain, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_mountain.ogg", volume));
+        tiles.insert(TileType::Snow, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_snow.ogg", volume));
+        tiles.insert(TileType::Lava, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_lava.ogg", volume));
+        tiles.insert(TileType::Teleport(false), OxAgSoundConfig::new_with_volume("assets/default/tile/tile_teleport.ogg", volume));
+        tiles.insert(TileType::Street, OxAgSoundConfig::new_with_volume("assets/default/tile/tile_street.ogg", volume));
+
+        // Configure weather
+        let mut weather = HashMap::new();
+        weather.insert(WeatherType::Rainy, OxAgSoundConfig::new_looped_with_volume("assets/default/weather/weather_rainy.ogg", 0.4 * volume));
+        weather.insert(WeatherType::Sunny, OxAgSoundConfig::new_looped_with_volume("assets/default/weather/weather_sunny.ogg", volume));
+
+        // Initialize audio
+        let audio = OxAgAudioTool::new(events, tiles, weather);
+        match audio {
+            Ok(audio) => audio,
+            Err(error) => panic!("Error while initializing audio: {:?}", error)
+        }
+        
+    }
+    /// Quantity of a content currently in the backpack
+    pub fn count_content(&self, content: &Content) -> usize {
+        // The backpack keys are not guaranteed to be the default content,
+        // so everything matching the same kind of content is summed
+        count_in(self.get_backpack().get_contents(), content)
+    }
+    // The backpack may be smaller than the configured thresholds,
+    // so they are clamped to what it can actually hold
+    fn clamp_to_backpack(&self, threshold: usize) -> usize {
+        threshold.min(self.get_backpack().get_size().max(1))
+    }
+    /// Thresholds of the content, clamped to the backpack size
+    pub fn thresholds(&self, content: &Content) -> Thresholds {
+        let thresholds = self.config.thresholds.get(&content.to_default()).cloned().unwrap_or_default();
+        Thresholds {
+            save_at: thresholds.save_at.map(|threshold| self.clamp_to_backpack(threshold)),
+            trade_at: thresholds.trade_at.map(|threshold| self.clamp_to_backpack(threshold)),
+            finish_at: thresholds.finish_at.map(|threshold| self.clamp_to_backpack(threshold)),
+        }
+    }
+    // Tells if any content in the backpack reached the threshold chosen by `pick`
+    fn threshold_reached(&self, pick: impl Fn(&Thresholds) -> Option<usize>) -> bool {
+        self.config.thresholds.keys().any(|content| {
+            match pick(&self.thresholds(content)) {
+                Some(threshold) => self.count_content(content) >= threshold,
+                None => false
+            }
+        })
+    }
+    // Only the contents that go to the banks can make the bot go saving
+    fn save_due(&self) -> bool {
+        self.config.bank_contents.iter().any(|content| {
+            match self.thresholds(content).save_at {
+                Some(threshold) => self.count_content(content) >= threshold,
+                None => false
+            }
+        })
+    }
+    fn trade_due(&self) -> bool {
+        self.threshold_reached(|thresholds| thresholds.trade_at)
+    }
+    fn finish_due(&self) -> bool {
+        self.threshold_reached(|thresholds| thresholds.finish_at)
+    }
+    /// Changes the goal, the deadline starts again from now
+    /// If the goal is raised after it was reached, the bot goes back collecting coins
+    pub fn set_goal(&mut self, goal: Option<usize>) {
+        self.goal = goal;
+        self.goal_set_tick = self.ticks;
+        if matches!(self.state, State::RockCollecting | State::Finish) && !self.goal_reached() {
+            // The enclosure is built again from the start at the next finish
+            self.asphalt_done = 0;
+            self.set_state(State::CoinCollecting);
+        }
+    }
+    // Tells if the time given to reach the goal is over
+    fn goal_expired(&self) -> bool {
+        match (self.goal, self.config.goal_deadline_ticks) {
+            (Some(goal), Some(deadline)) => self.saved < goal && self.ticks >= self.goal_set_tick + deadline,
+            _ => false
+        }
+    }
+    // The goal is reached when enough has been saved, or when there is no more time for it
+    fn goal_reached(&self) -> bool {
+        match self.goal {
+            Some(goal) => self.saved >= goal || self.goal_expired(),
+            None => false
+        }
+    }
+    // Once the goal is reached the bot collects rocks, unless the rock phase is skipped
+    fn state_after_goal(&self) -> State {
+        if self.config.skip_rock_phase {
+            State::Finish
+        } else {
+            State::RockCollecting
+        }
+    }
+    // Content to deposit now, the first of the bank contents that the bot carries
+    fn deposit_content(&self) -> Content {
+        self.config.bank_contents.iter()
+            .find(|content| self.count_content(content) > 0)
+            .or(self.config.bank_contents.first())
+            .map(|content| content.to_default())
+            .unwrap_or(Content::Coin(0))
+    }
+    // Quantity carried of the contents that go to the banks
+    fn bankable_count(&self) -> usize {
+        self.config.bank_contents.iter().map(|content| self.count_content(content)).sum()
+    }
+    // With a bank close enough a smaller load is already worth the trip
+    fn trip_load_ready(&self) -> bool {
+        match (self.config.target_trip_load, self.distance_to_chosen_bank()) {
+            (Some(load), Some(distance)) => self.bankable_count() >= load.max(1) && distance <= self.config.near_bank_distance,
+            _ => false
+        }
+    }
+    // Tells if the carried contents are enough for a trip to the bank started on demand
+    fn worth_saving(&self) -> bool {
+        let load = self.bankable_count();
+        load > 0 && load >= self.config.min_save_load
+    }
+    // Avoids bouncing between Trading and CoinCollecting every tick
+    fn can_trade_again(&self) -> bool {
+        match self.last_trade_tick {
+            Some(tick) => self.ticks >= tick + self.config.trade_cooldown_ticks,
+            None => true
+        }
+    }
+    fn trade(&mut self) {
+        // Recycle tool used here
+        self.last_trade_tick = Some(self.ticks);
+        let trade = recycle(self, 0);
+        match trade {
+            Ok(coins) => println!("You traded {} coins", coins),
+            Err(error) => {
+                println!("While trading there has been an issue {:?}", error);
+                self.errors.push(ActionError::Trade(format!("{:?}", error)));
+            }
+        }
+
+        if self.save_due() {
+            self.set_state(State::Saving)
+        }else {
+            self.set_state(State::CoinCollecting)
+        }
+    }
+    fn coin_collect(&mut self, world: &mut World) {
+        println!("Coin collecting");
+        if self.goal.is_some() && self.goal.unwrap() <= self.saved + self.count_content(&Content::Coin(0)) {
+            self.set_state(State::Saving);
+            return;
+        }
+        if self.goal_expired() {
+            // A trip only for a handful of coins is not worth it
+            if self.worth_saving() {
+                self.set_state(State::Saving);
+            } else {
+                self.set_state(self.state_after_goal());
+            }
+            return;
+        }
+        if let Some(pile) = self.next_pending_harvest() {
+            // Finish the pile that did not fit in the backpack last time
+            println!("Going back to the coins left at {:?}", pile);
+            if self.go_next_to(world, pile) {
+                // Destroying adds the pile again if it still doesn't fit
+                self.pending_harvest.retain(|(coord, _)| *coord != pile);
+                self.destroy_area(world);
+            }
+        } else if self.enough_known(&Content::Coin(0)) {
+            // Enough coins are already on the map, collect them instead of exploring
+            println!("Enough coins known, collecting the closest");
+            let before = self.count_content(&Content::Coin(0));
+            if self.go_to_nearest(world, Content::Coin(0)) {
+                self.destroy_area(world);
+            }
+            if self.count_content(&Content::Coin(0)) == before {
+                // The known coins can't be taken from here, look around instead
+                self.wander_in_seach_of(world, COIN_LOOKING_FOR.to_vec());
+            }
+        } else {
+            self.wander_in_seach_of(world, COIN_LOOKING_FOR.to_vec());
+        }
+        // Destroying with a full backpack may have already moved the bot to make room
+        if self.state != State::CoinCollecting {
+            return;
+        }
+
+        // Change state if too many coin to save or if there are enough to trade
+        if self.save_due() || self.trip_load_ready() {
+            self.set_state(State::Saving)
+        }else if self.trade_due() && self.can_trade_again() {
+            self.set_state(State::Trading)
+        }else if self.bank_nearby() && self.worth_saving() {
+            // With the bank so close the coins are deposited right away, in the same tick
+            self.set_state(State::Saving);
+            self.save(world);
+        }
+    }
+    // Closest pile left behind, if the backpack has room for it
+    // The piles that the seen tiles say are gone are forgotten
+    fn next_pending_harvest(&mut self) -> Option<(usize, usize)> {
+        let seen_index = &self.seen_index;
+        let seen = &self.seen;
+        self.pending_harvest.retain(|(coord, _)| match seen_index.get(coord) {
+            Some(index) => matches!(seen[*index].1.content, Content::Coin(amount) if amount > 0),
+            None => true,
+        });
+        if self.backpack_full() {
+            return None;
+        }
+        let here = self.current_position();
+        self.pending_harvest.iter()
+            .map(|(coord, _)| *coord)
+            .min_by_key(|coord| (manhattan(here, *coord), *coord))
+    }
+    // Remembers the coins still around the bot when the backpack is full, to come back for them
+    fn note_leftover_coins(&mut self, world: &mut World) {
+        if !self.backpack_full() {
+            return;
+        }
+        let (tiles, position) = where_am_i(self, world);
+        for (coord, tile) in neighborhood_cells(&tiles, position) {
+            if let Content::Coin(amount) = tile.content {
+                if amount == 0 || !self.in_region(coord) {
+                    continue;
+                }
+                match self.pending_harvest.iter_mut().find(|(pile, _)| *pile == coord) {
+                    Some(pile) => pile.1 = amount,
+                    None => {
+                        println!("Backpack full, {} coins left at {:?}", amount, coord);
+                        self.pending_harvest.push((coord, amount));
+                    }
+                }
+            }
+        }
+    }
+    // Tells if the seen tiles hold enough of the content to stop exploring
+    fn enough_known(&self, content: &Content) -> bool {
+        match self.config.enough_known_tiles {
+            Some(enough) => self.seen.iter().filter(|(_, tile)| tile.content.to_default() == content.to_default()).count() >= enough,
+            None => false
+        }
+    }
+    // Tells if collecting and banking can go together, with a free bank close by
+    fn bank_nearby(&self) -> bool {
+        self.config.collect_and_bank && self.distance_to_chosen_bank().map_or(false, |distance| distance <= self.config.collect_and_bank_radius)
+    }
+    fn destroy_area(&mut self, world: &mut World) {
+        // Nothing destroyed would fit, make room first
+        if self.backpack_full() {
+            println!("Backpack full, not destroying");
+            if self.config.free_space_before_destroy {
+                self.free_backpack_space();
+            }
+            return;
+        }
+        // Destroy zone tool used here
+        let mut banks_points = vec![];
+        if let Some(banks) = self.free_banks.get(&bank_key()) {
+            for bank in banks.iter() {
+                banks_points.push((bank.0.0, bank.0.1));
+            }
+        }
+        if let Some(banks) = self.filled_banks.get(&bank_key()) {
+            for bank in banks.iter() {
+                banks_points.push((bank.0.0, bank.0.1));
+            }
+        }
+        let (x, y) = (self.current_position().0 as i32, self.current_position().1 as i32);
+        // Tells if the cell touches a bank, also diagonally
+        let near_bank = |cell: (i32, i32), distance: i32| banks_points.iter()
+            .any(|bank| (bank.0 as i32 - cell.0).abs() <= distance && (bank.1 as i32 - cell.1).abs() <= distance);
+        // The destroy zone tool destroys all the 3x3 around the bot, none of those tiles can touch a bank
+        let good = !near_bank((x, y), 2);
+        // The destroy zone tool works all around the bot, so it can't be used near the region border
+        let (row, col) = self.current_position();
+        if good && self.in_region((row.saturating_sub(1), col.saturating_sub(1))) && self.in_region((row + 1, col + 1)) {
+            let needs: Vec<Content> = self.looking_for.iter().filter(|content| self.worth_destroying(content)).cloned().collect();
+            for content in needs.iter() {
+                let result = DestroyZone.execute(world, self, content.clone());
+                self.after_action(|| format!("destroy zone {:?}", content));
+                if let Err(error) = result {
+                    println!("While destroying the zone there has been an issue {:?}", error);
+                    self.errors.push(ActionError::DestroyZone(content.clone(), format!("{:?}", error)));
+                }
+            }
+        } else {
+            let (tiles, position) = where_am_i(self, world);
+            let center = neighborhood_center(&tiles, position);
+            'scan: for (i, row) in tiles.iter().enumerate() {
+                for (j, tile) in row.iter().enumerate() {
+                    // Only the four tiles next to the bot can be destroyed from here, not the diagonal ones
+                    let (tile, direction) = match (tile, direction_from_offset(center, (i, j))) {
+                        (Some(tile), Some(direction)) => (tile, direction),
+                        _ => continue
+                    };
+                    let target = match step_from(position, &direction) {
+                        Some(target) => target,
+                        None => continue
+                    };
+                    let content = tile.content.clone();
+                    // The tiles hold real quantities, the looked for contents are the default ones
+                    if self.looking_for.contains(&content.to_default()) && !matches!(content, Content::Bank(_)) && self.worth_destroying(&content) {
+                        // Already destroyed during this tick, nothing left there, or too close to a bank
+                        if self.destroyed.contains(&target) || !self.in_region(target) || near_bank((target.0 as i32, target.1 as i32), 1) {
+                            continue;
+                        }
+                        // Do not go below the energy needed for a step
+                        if !self.get_energy().has_enough_energy(self.config.per_step_energy) {
+                            break 'scan;
+                        }
+                        self.last_added = None;
+                        let thing = destroy(self, world, direction.clone());
+                        self.after_action(|| format!("destroy {:?}: {:?}", direction, thing));
+                        match thing {
+                            Ok(number) => {
+                                println!("Destroyed {} {:?}", number, content);
+                                self.destroyed.insert(target);
+                                self.confirm_pickup(&content, number);
+                            },
+                            Err(error) => {
+                                println!("While destroying there has been an issue {:?}", error);
+                                self.errors.push(ActionError::Destroy(direction, error));
+                            }
+                        }
+                    }
+                }
+            }
+
+        }
+        // A pile bigger than the room left is only partly taken
+        if self.looking_for.contains(&Content::Coin(0)) {
+            self.note_leftover_coins(world);
+        }
+    }
+    // The world tells what really got in the backpack, a destroy without it means the content was lost
+    fn confirm_pickup(&mut self, content: &Content, destroyed: usize) {
+        if destroyed == 0 {
+            return;
+        }
+        match self.last_added.take() {
+            Some((added, _)) if added == content.to_default() => {},
+            _ => {
+                println!("Destroyed {} {:?} but nothing was added to the backpack", destroyed, content);
+                self.missed_pickups.push((content.to_default(), destroyed));
+            }
+        }
+    }
+    /// Destroys that didn't add anything to the backpack, as (content, destroyed quantity)
+    pub fn missed_pickups(&self) -> &[(Content, usize)] {
+        &self.missed_pickups
+    }
+    // Value of the content for the bot, 1 if not configured
+    fn content_value(&self, content: &Content) -> usize {
+        self.config.content_values.get(&content.to_default()).cloned().unwrap_or(1)
+    }
+    // Tells if the bot carries as much of the content as it is allowed to
+    fn at_collection_cap(&self, content: &Content) -> bool {
+        match self.config.collection_caps.get(&content.to_default()) {
+            Some(cap) => self.count_content(content) >= *cap,
+            None => false
+        }
+    }
+    // The conservative strategy keeps the energy low on for walking, destroying only what is worth it
+    fn worth_destroying(&self, content: &Content) -> bool {
+        if self.at_collection_cap(content) {
+            return false;
+        }
+        match self.config.destroy_strategy {
+            DestroyStrategy::Greedy => true,
+            DestroyStrategy::Conservative => {
+                if self.get_energy().has_enough_energy(self.config.conservative_energy_margin) {
+                    return true;
+                }
+                let carried: usize = self.get_backpack().get_contents().values().sum();
+                let room = self.get_backpack().get_size().saturating_sub(carried);
+                self.content_value(content) >= self.config.conservative_min_value && room >= self.config.conservative_min_room
+            }
+        }
+    }
+    fn rock_collect(&mut self, world: &mut World) {
+        println!("Rock collecting");
+        // remove all coins from the backpack
+        let coins = self.count_content(&Content::Coin(0));
+        self.drop_content(world, Content::Coin(0), coins);
+        // remove all garbage from the backpack
+        let garbage = self.count_content(&Content::Garbage(0));
+        self.drop_content(world, Content::Garbage(0), garbage);
+        // Recycle tool used here, the trees carried can become rocks
+        if self.config.recycle_trees && self.count_content(&Content::Tree(0)) > 0 {
+            let before = self.count_content(&Content::Rock(0));
+            match recycle(self, 0) {
+                Ok(_) => println!("Recycled trees, rocks went from {} to {}", before, self.count_content(&Content::Rock(0))),
+                Err(error) => {
+                    println!("While recycling trees there has been an issue {:?}", error);
+                    self.errors.push(ActionError::Trade(format!("{:?}", error)));
+                }
+            }
+        }
+        self.wander_in_seach_of(world, ROCK_LOOKING_FOR.to_vec());
+        let current_number_rock = self.count_content(&Content::Rock(0));
+        println!("CURRENT number of rock: {:?}", current_number_rock);
+        // Change state if enough rock
+        if self.finish_due() {
+            self.set_state(State::Finish)
+        }
+    }
+    fn enjoy(&mut self) {
+        // Does nothing
+        println!("Enjoying");
+        self.idle_reason = Some(IdleReason::Done);
+    }
+    fn search_for_bank(&mut self, world: &mut World) {
+        println!("Searching for bank");
+        if self.bankable_count() == 0 && !self.shutting_down {
+            if self.goal_reached() {
+                self.set_state(self.state_after_goal());
+            } else {
+                self.fall_back("Nothing to bring to a bank");
+            }
+            return;
+        }
+        if self.nearest_free_bank().is_some() {
+            self.bank_search_ticks = 0;
+            self.search_radius = self.config.search_radius;
+            self.set_state(State::Saving);
+        } else {
+            self.bank_search_ticks += 1;
+            // Every few ticks without a bank the search looks a bit further
+            if self.config.bank_search_retry_limit > 0
+                && self.bank_search_ticks % self.config.bank_search_retry_limit == 0
+                && self.search_radius < self.config.max_search_radius {
+                self.search_radius = (self.search_radius * 2).min(self.config.max_search_radius);
+                println!("No bank found in {} ticks, search radius now {}", self.bank_search_ticks, self.search_radius);
+            }
+            // All the known banks are full and the backpack too, look further away
+            if self.backpack_full() && !self.known_banks(BankStatus::Filled).is_empty() {
+                self.search_radius = self.search_radius.max(self.config.full_banks_search_radius);
+                if self.bank_search_ticks > self.config.bank_search_deadline {
+                    println!("No free bank found, giving up on banking {} contents", self.bankable_count());
+                    self.bank_search_ticks = 0;
+                    self.search_radius = self.config.search_radius;
+                    self.set_state(self.state_after_goal());
+                    return;
+                }
+            }
+            self.look_for_unknown_banks(world);
+            self.wander_in_seach_of(world, BANK_LOOKING_FOR.to_vec());
+        }
+    }
+    // Moves to the state that empties the backpack while collecting
+    fn free_backpack_space(&mut self) {
+        match self.state {
+            State::CoinCollecting => {
+                if self.worth_saving() {
+                    self.set_state(State::Saving);
+                } else if self.can_trade_again() {
+                    self.set_state(State::Trading);
+                }
+            },
+            State::RockCollecting => {
+                self.set_state(State::Finish);
+            },
+            _ => {}
+        }
+    }
+    // Tells if the backpack can't take anything else
+    fn backpack_full(&self) -> bool {
+        let carried: usize = self.get_backpack().get_contents().values().sum();
+        carried >= self.get_backpack().get_size()
+    }
+    fn go_to_closest_open_bank(&mut self, world: &mut World) -> Option<Direction> {
+        let know_bank = self.nearest_free_bank().is_some();
+        let mut travel = None;
+        if know_bank {
+            let (x, y) = self.closest_bank();
+            println!("Closest bank is at {:?} {:?}", x, y);
+            travel = Some((manhattan(self.current_position(), (x, y)), self.steps));
+            self.go_next_to(world, (x, y));
+        } else {
+            self.wander_in_seach_of(world, BANK_LOOKING_FOR.to_vec());
+        }
+
+        let direction = self.adjacent_bank_direction(world);
+        if let (Some((estimated, start)), Some(_)) = (travel, &direction) {
+            // The bank has been reached, compare the real path with the estimate
+            self.last_travel = Some((estimated, self.steps - start));
+        }
+        direction
+    }
+    /// Estimated (Manhattan) and actual number of steps of the last trip to a bank
+    pub fn last_travel_error(&self) -> Option<(usize, usize)> {
+        self.last_travel
+    }
+    fn adjacent_bank_direction(&mut self, world: &mut World) -> Option<Direction> {
+        let (neighborhoods, position) = where_am_i(self, &world);
+        let center = neighborhood_center(&neighborhoods, position);
+        for (x, row) in neighborhoods.iter().enumerate() {
+            for (y, tile) in row.iter().enumerate() {
+                if let Some(tile) = tile {
+                    match &tile.content.to_default() {
+                        Content::Bank(_) => {
+                            if let Some(dir) = direction_from_offset(center, (x, y)) {
+                                return Some(dir);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        None
+    }
+    // Saves a bank never seen before in the free banks, returns false if it was already known
+    fn record_bank(&mut self, coord: (usize, usize)) -> bool {
+        if self.is_free_bank(coord) || self.is_filled_bank(coord) {
+            return false;
+        }
+        self.free_banks.save(&bank_key(), &ChartedCoordinate(coord.0, coord.1));
+        if let Some(callback) = self.on_bank_found.as_mut() {
+            callback(coord);
+        }
+        true
+    }
+    /// Sets a function called once for every new bank the bot discovers
+    pub fn set_on_bank_found(&mut self, callback: impl FnMut((usize, usize)) + 'static) {
+        self.on_bank_found = Some(Box::new(callback));
+    }
+    fn look_for_unknown_banks(&mut self, world: &mut World) {
+        let (neighborhoods, position) = where_am_i(self, &world);
+
+        // Searching if nearby there is a bank in the range
+        for (coord, tile) in neighborhood_cells(&neighborhoods, position) {
+            match &tile.content.to_default() {
+                Content::Bank(_) => {
+                    self.record_bank(coord);
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Makes the random choices of the bot reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+    // Next point of the exploration pattern, None when exploring randomly
+    fn pattern_waypoint(&self) -> Option<(usize, usize)> {
+        let spacing = self.config.pattern_spacing.max(1);
+        let extent = self.config.pattern_extent.max(spacing);
+        let waypoint = match self.config.exploration_pattern {
+            ExplorationPattern::Random => return None,
+            ExplorationPattern::Spiral => {
+                // Corners of squares growing around home, starting again from the smallest
+                let home = self.home.unwrap_or(self.current_position());
+                let rings = extent / spacing;
+                let distance = (self.pattern_step / 4 % rings + 1) * spacing;
+                match self.pattern_step % 4 {
+                    0 => (home.0.saturating_sub(distance), home.1.saturating_sub(distance)),
+                    1 => (home.0.saturating_sub(distance), home.1 + distance),
+                    2 => (home.0 + distance, home.1 + distance),
+                    _ => (home.0 + distance, home.1.saturating_sub(distance)),
+                }
+            },
+            ExplorationPattern::Lawnmower => {
+                // Rows from the top of the area, alternating left to right and right to left
+                let (min, max) = self.region.unwrap_or(((0, 0), (extent, extent)));
+                let rows = (max.0 - min.0) / spacing + 1;
+                let step = self.pattern_step % (rows * 2);
+                let row = min.0 + step / 2 * spacing;
+                let left_to_right = (step / 2) % 2 == 0;
+                let at_row_start = step % 2 == 0;
+                (row, if left_to_right == at_row_start { min.1 } else { max.1 })
+            }
+        };
+        Some(self.clamp_to_region(waypoint))
+    }
+    // Chooses where to search, using the quadrant weights if configured
+    fn pick_quadrant(&mut self, candidates: &[SearchDirection]) -> SearchDirection {
+        if let Some(weights) = self.config.quadrant_weights {
+            let weights: Vec<f64> = candidates.iter().map(|direction| weights[quadrant_index(direction)]).collect();
+            if let Ok(distribution) = WeightedIndex::new(&weights) {
+                return clone_direction(&candidates[distribution.sample(&mut self.rng)]);
+            }
+        }
+        clone_direction(&candidates[self.rng.gen_range(0..candidates.len())])
+    }
+    fn wander_in_seach_of(&mut self, world: &mut World, contents: Vec<Content>) {
+        self.destroy_area(world);
+
+        self.timer += 1;
+        if self.config.replan_interval > 0 && self.timer % self.config.replan_interval == 0 {
+            self.replan();
+        }
+        let mut where_can_i_go = vec![];
+        let (x, y) = self.current_position();
+
+        for direction in DIRECTIONS.iter() {
+            match direction {
+                SearchDirection::BottomLeft => {
+                    let (cx, cy) = (Some(x + 2), y.checked_sub(2));
+                    if self.unseen_quadrant(cx, cy) {
+                        where_can_i_go.push(SearchDirection::BottomLeft);
+                    }
+                },
+                SearchDirection::BottomRight => {
+                    let (cx, cy) = (Some(x + 2), Some(y + 2));
+                    if self.unseen_quadrant(cx, cy) {
+                        where_can_i_go.push(SearchDirection::BottomRight);
+                    }
+                },
+                SearchDirection::TopLeft => {
+                    let (cx, cy) = (x.checked_sub(2), y.checked_sub(2));
+                    if self.unseen_quadrant(cx, cy) {
+                        where_can_i_go.push(SearchDirection::TopLeft);
+                    }
+                },
+                SearchDirection::TopRight => {
+                    let (cx, cy) = (x.checked_sub(2), Some(y + 2));
+                    if self.unseen_quadrant(cx, cy) {
+                        where_can_i_go.push(SearchDirection::TopRight);
+                    }
+                }
+            }
+        }
+
+        if where_can_i_go.len() == 0 {
+            where_can_i_go.push(SearchDirection::BottomLeft);
+            where_can_i_go.push(SearchDirection::BottomRight);
+            where_can_i_go.push(SearchDirection::TopLeft);
+            where_can_i_go.push(SearchDirection::TopRight);
+        }
+
+        // Explore toward the requested area, if any, or follow the exploration pattern
+        let waypoint = self.explore_target.or_else(|| self.pattern_waypoint());
+        let search_direction = match waypoint {
+            Some(target) => search_direction_toward((x, y), target),
+            None => self.pick_quadrant(&where_can_i_go)
+        };
+
+        // Look if something interesting nearby with the tool, the most valuable content first
+        let mut ordered = contents.clone();
+        ordered.sort_by_key(|content| std::cmp::Reverse(self.content_value(content)));
+        let mut found: HashMap<Content, Vec<(usize, usize)>> = HashMap::new();
+        let mut searched = false;
+        let radius = self.search_radius;
+        for content in ordered.iter() {
+            self.trace_decision(format!("Searching for {:?}", content));
+            let mut st = SearchTool::new();
+            match st.look_for_this_content(self, world, vec![content.clone()], radius, clone_direction(&search_direction)) {
+                Ok(_) => {
+                    searched = true;
+                    for (found_content, coords) in st.found_content_coords.iter() {
+                        found.entry(found_content.clone()).or_insert(vec![]).extend(coords.iter().cloned());
+                    }
+                },
+                Err(e) => println!("Error: {:?}", e)
+            }
+            // Less valuable contents are searched only if nothing better is around
+            if found.values().any(|coords| !coords.is_empty()) {
+                break;
+            }
+        }
+        self.last_search_results = found.clone();
+        if searched {
+            // Save the banks into the map
+            if contents.iter().any(|content| content.to_default() == bank_key()) {
+                for (_, coord) in found.iter() {
+                    for (posx, posy) in coord {
+                        self.record_bank((*posx, *posy));
+                    }
+                }
+            }else {
+                let mut heap = BinaryHeap::new();
+                // Pupulate heap for the most valuable stuff for the distance
+                let (x, y) = self.current_position();
+                for (content, coord) in found.iter() {
+                    // No point in going where nothing more can be taken
+                    if self.at_collection_cap(content) {
+                        continue;
+                    }
+                    for (posx, posy) in coord {
+                        let dist = manhattan((x, y), (*posx, *posy));
+                        heap.push((self.target_score(content, dist), (posx.clone(), posy.clone())));
+                    }
+                }
+
+                self.reach_found_targets(world, heap);
+            }
+        }
+        for _ in 0..4 {
+            if self.out_of_time() {
+                break;
+            }
+            let towards = waypoint.and_then(|target| direction_toward(self.current_position(), target));
+            match towards {
+                Some(direction) => {
+                    self.try_go(world, direction);
+                },
+                None => {
+                    // Prefer the directions that lead to tiles never seen
+                    let unseen = self.unseen_directions(world);
+                    let direction = if unseen.is_empty() {
+                        [Direction::Up, Direction::Down, Direction::Left, Direction::Right][self.rng.gen_range(0..4)].clone()
+                    } else {
+                        unseen[self.rng.gen_range(0..unseen.len())].clone()
+                    };
+                    self.try_go(world, direction);
+                }
+            }
+        }
+        if let Some(target) = self.explore_target {
+            if manhattan(self.current_position(), target) <= 1 {
+                println!("Reached the exploration target {:?}", target);
+                self.explore_target = None;
+            }
+        } else if let Some(target) = waypoint {
+            if manhattan(self.current_position(), target) <= 1 {
+                self.pattern_step += 1;
+            }
+        }
+        
+    }
+    // Reaches the found targets in the order of the heap, returns how many were visited
+    // Targets left in the heap are found again by the next search if still there
+    fn reach_found_targets(&mut self, world: &mut World, mut heap: BinaryHeap<(usize, (usize, usize))>) -> usize {
+        let mut visited = 0;
+        while self.get_energy().has_enough_energy(self.config.wander_traverse_reserve) && heap.len() > 0 && !self.out_of_time() {
+            if let Some(max) = self.config.max_wander_targets_per_tick {
+                if visited >= max {
+                    break;
+                }
+            }
+            visited += 1;
+            let (_, (x, y)) = heap.pop().unwrap();
+            self.trace_decision(format!("Going to the found target at {:?}", (x, y)));
+            let _ = self.reach_position(world, x, y);
+            self.destroy_area(world);
+        }
+        visited
+    }
+    // Priority of a found target in the wander, the most valuable for the distance first
+    fn target_score(&self, content: &Content, distance: usize) -> usize {
+        self.content_value(content) * 1000 / (distance + 1)
+    }
+    /// Coordinates of the contents found by the search tool in the last wander
+    pub fn last_search_results(&self) -> &HashMap<Content, Vec<(usize, usize)>> {
+        &self.last_search_results
+    }
+    /// Makes the bot explore toward (x, y) while it keeps collecting,
+    /// the request is forgotten once the bot gets there
+    pub fn explore_toward(&mut self, x: usize, y: usize) {
+        self.explore_target = Some((x, y));
+    }
+    fn nearest_free_bank(&self) -> Option<((usize, usize), usize)> {
+        let robot = self.current_position();
+        let mut closest = None;
+        let mut closest_reachable = None;
+
+        if let Some(bank) = self.free_banks.get(&bank_key()) {
+            for (coord, _) in bank.iter() {
+                let coord = (coord.0, coord.1);
+                if self.reserved_by_others(coord) || !self.bank_accepts_content(coord, &self.deposit_content()) {
+                    continue;
+                }
+                let dist = manhattan(robot, coord);
+                // Dangerous surroundings make the bank look farther than it is
+                let score = dist + self.bank_hazard(coord) * self.config.bank_hazard_penalty;
+                // Equally distant banks are ordered by coordinate, the map iteration order is not stable
+                match closest {
+                    Some((best, _, best_score)) if (best_score, best) <= (score, coord) => {},
+                    _ => closest = Some((coord, dist, score))
+                }
+                match closest_reachable {
+                    Some((best, _, best_score)) if (best_score, best) <= (score, coord) => {},
+                    _ => if self.has_known_path(coord) {
+                        closest_reachable = Some((coord, dist, score));
+                    }
+                }
+            }
+        }
+        // Banks with a known path first, the Manhattan closest if none
+        closest_reachable.or(closest).map(|(coord, dist, _)| (coord, dist))
+    }
+    // Number of seen tiles next to the bank that are dangerous or can't be walked on
+    fn bank_hazard(&self, coord: (usize, usize)) -> usize {
+        [Direction::Up, Direction::Down, Direction::Left, Direction::Right].iter()
+            .filter_map(|direction| step_from(coord, direction))
+            .filter_map(|neighbor| self.seen_index.get(&neighbor))
+            .filter(|index| {
+                let tile_type = &self.seen[**index].1.tile_type;
+                *tile_type == TileType::Lava || !is_walkable(tile_type)
+            })
+            .count()
+    }
+    // Tells if the bot knows a path of seen walkable tiles that gets next to the point
+    fn has_known_path(&self, to: (usize, usize)) -> bool {
+        let start = (self.current_position().0 as i32, self.current_position().1 as i32);
+        let to = (to.0 as i32, to.1 as i32);
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            if (x - to.0).abs() + (y - to.1).abs() <= 1 {
+                return true;
+            }
+            for next in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if visited.contains(&next) {
+                    continue;
+                }
+                if next.0 < 0 || next.1 < 0 {
+                    continue;
+                }
+                if let Some(index) = self.seen_index.get(&(next.0 as usize, next.1 as usize)) {
+                    if is_walkable(&self.seen[*index].1.tile_type) {
+                        visited.insert(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+    /// Manhattan distance to the closest known free bank, if any
+    /// The bank the bot goes saving to can be farther, see `nearest_free_bank`
+    pub fn distance_to_nearest_bank(&self) -> Option<usize> {
+        let robot = self.current_position();
+        self.known_banks(BankStatus::Free).into_iter().map(|bank| manhattan(robot, bank)).min()
+    }
+    // Distance to the bank the bot would go saving to
+    fn distance_to_chosen_bank(&self) -> Option<usize> {
+        self.nearest_free_bank().map(|(_, distance)| distance)
+    }
+    fn closest_bank(&mut self) -> (usize, usize) {
+        self.nearest_free_bank().map(|(coord, _)| coord).unwrap_or((0, 0))
+    }
+    /// Shares the bank reservations with other bots, so they don't go to the same bank
+    pub fn set_reservations(&mut self, reservations: Arc<Mutex<HashSet<(usize, usize)>>>) {
+        self.reservations = Some(reservations);
+    }
+    /// Reserves the bank for this bot, returns false if another bot already reserved it
+    pub fn reserve_bank(&mut self, coord: (usize, usize)) -> bool {
+        if self.reserved_by_others(coord) {
+            return false;
+        }
+        if let Some(reservations) = &self.reservations {
+            if let Ok(mut reservations) = reservations.lock() {
+                reservations.insert(coord);
+            }
+        }
+        self.reserved.insert(coord);
+        true
+    }
+    /// Releases a bank reserved by this bot
+    pub fn release_bank(&mut self, coord: (usize, usize)) {
+        if self.reserved.remove(&coord) {
+            if let Some(reservations) = &self.reservations {
+                if let Ok(mut reservations) = reservations.lock() {
+                    reservations.remove(&coord);
+                }
+            }
+        }
+    }
+    fn reserved_by_others(&self, coord: (usize, usize)) -> bool {
+        if self.reserved.contains(&coord) {
+            return false;
+        }
+        match &self.reservations {
+            Some(reservations) => reservations.lock().map(|reservations| reservations.contains(&coord)).unwrap_or(false),
+            None => false
+        }
+    }
+    // Picks up the loose coins next to the bot, so they get deposited too
+    fn sweep_coins(&mut self, world: &mut World) {
+        for (direction, tile) in self.scan_adjacent(world).into_iter().flatten() {
+            let coins = matches!(tile.content, Content::Coin(_));
+            if !coins || self.backpack_full() || !self.get_energy().has_enough_energy(self.config.per_step_energy) {
+                continue;
+            }
+            self.last_added = None;
+            let result = destroy(self, world, direction.clone());
+            self.after_action(|| format!("sweep {:?}: {:?}", direction, result));
+            match result {
+                Ok(number) => {
+                    println!("Swept {} coins before depositing", number);
+                    self.confirm_pickup(&Content::Coin(0), number);
                 },
-                SearchDirection::TopLeft => {
-                    let (cx, cy) = (x - 2, y - 2);
-                    if !self.check_if_seen(cx, cy) {
-                        where_can_i_go.push(SearchDirection::TopLeft);
+                Err(error) => self.errors.push(ActionError::Destroy(direction, error))
+            }
+        }
+    }
+    // Order of the directions tried when depositing, the configured one
+    // or the one toward the bank first
+    fn deposit_directions(&self, bank: (usize, usize)) -> Vec<Direction> {
+        if let Some(order) = &self.config.deposit_order {
+            return order.clone();
+        }
+        let mut order = vec![Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+        if let Some(toward) = direction_toward(self.current_position(), bank) {
+            order.retain(|direction| std::mem::discriminant(direction) != std::mem::discriminant(&toward));
+            order.insert(0, toward);
+        }
+        order
+    }
+    fn save(&mut self, world: &mut World) {
+        println!("Saving");
+        if self.bankable_count() == 0 && !self.shutting_down && !self.goal_reached() {
+            self.fall_back("Nothing to save");
+            return;
+        }
+        let (cx, cy) = self.closest_bank();
+        if self.nearest_free_bank().is_some() {
+            self.reserve_bank((cx, cy));
+        }
+        let (x, y) = self.current_position();
+         
+        let mut direction = self.go_to_closest_open_bank(world);
+
+        if (cx == x) && (cy == y) {
+            // Step away toward a tile that can be walked on, looking before moving
+            let adjacent = self.scan_adjacent(world);
+            for dir in self.deposit_directions((cx, cy)) {
+                let walkable = adjacent.iter().flatten()
+                    .any(|(side, tile)| std::mem::discriminant(side) == std::mem::discriminant(&dir) && is_walkable(&tile.tile_type));
+                if walkable && self.try_go(world, dir) {
+                    break;
+                }
+            }
+            direction = self.adjacent_bank_direction(world);
+        }
+        let content = self.deposit_content();
+        // With more banks around, the one chosen is tried first, among the ones taking the content
+        if direction.is_some() {
+            direction = None;
+            for dir in self.deposit_directions((cx, cy)) {
+                if self.bank_in_direction(world, &dir) && self.accepts_in_direction(&dir, &content) {
+                    direction = Some(dir);
+                    break;
+                }
+            }
+        }
+        // Never put the coins on the ground, only in a bank seen next to the bot
+        if let Some(dir) = &direction {
+            if !self.bank_in_direction(world, dir) {
+                println!("No bank {:?} of the bot, not depositing", dir);
+                direction = None;
+            }
+        }
+        if let Some(dir) = direction {
+            // The bank actually next to the bot, it can be another one than the closest
+            let bank = step_from(self.current_position(), &dir).unwrap_or((cx, cy));
+            if self.config.sweep_before_deposit {
+                self.sweep_coins(world);
+            }
+            let carried = self.count_content(&content);
+            // Do not bring more than what the bank can still accept, if known
+            let requested = match self.bank_capacity.get(&bank) {
+                Some(capacity) => carried.min(*capacity),
+                None => carried
+            };
+            // With nothing to deposit put gives back 0 as for a full bank,
+            // so it is not called and the bank stays free
+            let putting = if carried == 0 {
+                Some(0)
+            } else {
+                self.try_put(world, content.clone(), requested, dir.clone())
+            };
+            // Once at the bank the reservation is not needed anymore
+            self.release_bank((cx, cy));
+            match putting {
+                Some(quantity) => {
+                    self.put_failures.remove(&bank);
+                    *self.bank_accepted.entry(bank).or_insert(0) += quantity;
+                    if let Some(capacity) = self.bank_capacity.get_mut(&bank) {
+                        *capacity = capacity.saturating_sub(quantity);
+                    }
+                    if quantity < requested {
+                        // The bank took less than asked, so now it's full
+                        self.bank_capacity.insert(bank, 0);
+                    }
+                    let full = self.bank_capacity.get(&bank) == Some(&0);
+                    if full {
+                        self.fill_bank(bank);
+                    }
+                    if content == Content::Coin(0) {
+                        self.saved += quantity;
+                    }
+                    *self.deposited.entry(content.clone()).or_insert(0) += quantity;
+                    println!("Saved {quantity} {:?}", content);
+
+                    // Update the used banks in the hashmap
+                    let mut value = 0;
+                    if let Some(coins) = self.used_banks.get(&bank) {
+                        value = coins.clone();
+                    }
+                    self.used_banks.insert(bank, value + quantity);
+                    self.used_banks_tick.insert(bank, self.ticks);
+                    self.evict_used_banks(bank);
+
+                    if self.shutting_down {
+                        // Done only when nothing is left in the backpack
+                        if self.bankable_count() == 0 {
+                            self.set_state(State::Enjoying);
+                        }
+                    }else if self.count_content(&content) > 0 && self.nearest_free_bank().is_some() {
+                        // Spread what is left over the other known banks
+                        println!("Still carrying {} {:?}, going to the next bank", self.count_content(&content), content);
+                        // The bank turned out full, go to the next one now if there is energy for the trip
+                        // Every call fills a bank, so this ends with the known free banks
+                        let (_, distance) = self.nearest_free_bank().unwrap();
+                        if full && !self.out_of_time() && self.get_energy().has_enough_energy(self.config.per_step_energy * (distance + 1)) {
+                            self.save(world);
+                        }
+                    }else if self.bankable_count() > 0 && self.nearest_free_bank().is_some() {
+                        // Another content is left, it goes to its bank from the next tick
+                        println!("Still carrying {:?} for the banks", self.deposit_content());
+                    }else if self.goal_reached() {
+                        self.set_state(self.state_after_goal());
+                    }else {
+                        self.set_state(State::CoinCollecting);
                     }
                 },
-                SearchDirection::TopRight => {
-                    let (cx, cy) = (x - 2, y + 2);
-                    if !self.check_if_seen(cx, cy) {
-                        where_can_i_go.push(SearchDirection::TopRight);
+                None => {
+                    println!("While saving there has been an issue depositing {:?}", content);
+                    // A bank that keeps failing is given up, so the bot does not get stuck on it
+                    let failures = self.put_failures.entry(bank).or_insert(0);
+                    *failures += 1;
+                    if *failures >= self.config.max_put_failures {
+                        println!("Giving up the bank at {:?} after {} failed deposits", bank, failures);
+                        self.put_failures.remove(&bank);
+                        self.fill_bank(bank);
+                    }
+                }
+            }
+        } else {
+            // Not at the bank, the reservation is made again when the bot goes back saving
+            self.release_bank((cx, cy));
+            if self.shutting_down {
+                self.set_state(State::BankSearching);
+            }else if self.goal_reached() {
+                self.set_state(self.state_after_goal());
+            }else {
+                self.set_state(State::BankSearching);
+            }
+        }
+    }
+    // Projects of the enclosure of the bank, one for every side going clockwise from the configured corner.
+    // The first side is laid whole, the next ones start after the corner already done and the last one
+    // closes on the first tile. Every rectangle is laid from its top left tile, given with its number of tiles
+    fn enclosure_projects(&self, bank: (usize, usize)) -> Option<Vec<(Shape, usize, (usize, usize))>> {
+        let mut projects = vec![];
+        let mut corner = self.config.approach_corner;
+        for side in 0..ASPHALT_PROJECTS {
+            let ((a, b), (c, d)) = (corner.offset(), corner.clockwise().offset());
+            let first = if side == 0 { 0 } else { 1 };
+            let mut tiles = vec![];
+            for i in first..3 {
+                tiles.push(offset_from(bank, (a + (c - a) / 2 * i, b + (d - b) / 2 * i))?);
+            }
+            let top_left = (tiles.iter().map(|tile| tile.0).min()?, tiles.iter().map(|tile| tile.1).min()?);
+            let shape = if a == c {
+                Shape::Rectangle(tiles.len() as _, 1)
+            } else {
+                Shape::Rectangle(1, tiles.len() as _)
+            };
+            projects.push((shape, tiles.len(), top_left));
+            corner = corner.clockwise();
+        }
+        Some(projects)
+    }
+    // Builds the projects around the bank not built yet, stopping at the first that doesn't fit
+    // in the energy left. Returns how many projects were built in this call
+    fn asphalt_around(&mut self, world: &mut World) -> usize {
+        // Asphaltinator tool used here
+        let mut asphaltinator = Asphaltinator::new();
+        let projects = match self.asphalt_bank.and_then(|bank| self.enclosure_projects(bank)) {
+            Some(projects) => projects,
+            None => {
+                println!("No room around the bank for the asphalt");
+                return 0;
+            }
+        };
+        let mut built = 0;
+        for (shape, tiles, (row, col)) in projects.into_iter().skip(self.asphalt_done) {
+            // Waiting for the energy at the start of the project, to build it as soon as it fits
+            if !self.reach_position(world, row, col) {
+                println!("Start of the next asphalt project not reached");
+                break;
+            }
+            let estimate = tiles * self.config.asphalt_tile_energy;
+            if !self.get_energy().has_enough_energy(estimate) {
+                println!("Not enough energy for the next asphalt project, {} needed", estimate);
+                break;
+            }
+            match asphaltinator.design_project(shape) {
+                Ok(project) => {
+                    match asphaltinator.asfalting(self, world, project) {
+                        Ok(_) => {
+                            self.asphalt_done += 1;
+                            built += 1;
+                        },
+                        Err(error) => {
+                            println!("While asphaltinating there has been an issue {:?}", error);
+                            self.errors.push(ActionError::Asphalt(format!("{:?}", error)));
+                            break;
+                        }
                     }
+                },
+                Err(error) => {
+                    println!("While asphaltinating there has been an issue {:?}", error);
+                    self.errors.push(ActionError::Asphalt(format!("{:?}", error)));
+                    break;
+                }
+            }
+        }
+        built
+    }
+    /// Adds a bank that the bot has not discovered by itself
+    /// Returns false if the bank was already known
+    pub fn add_known_bank(&mut self, coord: (usize, usize), status: BankStatus, capacity: Option<usize>) -> bool {
+        if self.is_free_bank(coord) || self.is_filled_bank(coord) {
+            return false;
+        }
+        match status {
+            BankStatus::Free => {
+                self.free_banks.save(&bank_key(), &ChartedCoordinate(coord.0, coord.1));
+            },
+            BankStatus::Filled => {
+                self.filled_banks.save(&bank_key(), &ChartedCoordinate(coord.0, coord.1));
+            }
+        }
+        if let Some(capacity) = capacity {
+            self.bank_capacity.insert(coord, capacity);
+        }
+        true
+    }
+    /// Marks a known free bank as filled, for example when another bot filled it,
+    /// so the bot stops going there. Returns false if the bank was not known as free
+    pub fn mark_bank_filled(&mut self, coord: (usize, usize)) -> bool {
+        if !self.is_free_bank(coord) {
+            return false;
+        }
+        self.fill_bank(coord);
+        self.bank_capacity.insert(coord, 0);
+        self.release_bank(coord);
+        true
+    }
+    /// Limits the contents a bank accepts, the bot brings there only those
+    pub fn set_bank_accepts(&mut self, coord: (usize, usize), contents: Vec<Content>) {
+        self.bank_accepts.insert(coord, contents.iter().map(|content| content.to_default()).collect());
+    }
+    // Tells if the content can be deposited in the bank
+    fn bank_accepts_content(&self, coord: (usize, usize), content: &Content) -> bool {
+        match self.bank_accepts.get(&coord) {
+            Some(contents) => contents.contains(&content.to_default()),
+            None => true
+        }
+    }
+    // Tells if the bank next to the bot in the direction takes the content
+    fn accepts_in_direction(&self, direction: &Direction, content: &Content) -> bool {
+        step_from(self.current_position(), direction).map_or(false, |bank| self.bank_accepts_content(bank, content))
+    }
+    /// Total capacity of the bank as learned from the deposits, if known
+    pub fn learned_capacity(&self, coord: (usize, usize)) -> Option<usize> {
+        let accepted = self.bank_accepted.get(&coord).cloned().unwrap_or(0);
+        self.bank_capacity.get(&coord).map(|remaining| accepted + remaining)
+    }
+    // Moves a bank from the free ones to the filled ones
+    fn fill_bank(&mut self, coord: (usize, usize)) {
+        let _ = self.free_banks.remove(&bank_key(), ChartedCoordinate(coord.0, coord.1));
+        if !self.is_filled_bank(coord) {
+            self.filled_banks.save(&bank_key(), &ChartedCoordinate(coord.0, coord.1));
+        }
+        self.bank_cooldowns.insert(coord, self.ticks);
+    }
+    /// Coordinates of the known banks with the given status
+    pub fn known_banks(&self, status: BankStatus) -> Vec<(usize, usize)> {
+        let map = match status {
+            BankStatus::Free => &self.free_banks,
+            BankStatus::Filled => &self.filled_banks
+        };
+        let mut banks = vec![];
+        if let Some(coords) = map.get(&bank_key()) {
+            for (bank, _) in coords.iter() {
+                banks.push((bank.0, bank.1));
+            }
+        }
+        banks
+    }
+    /// Full status report of the bot, the same printed at every tick
+    pub fn describe(&self) -> String {
+        let mut backpack: Vec<String> = self.get_backpack().get_contents().iter()
+            .filter(|(_, quantity)| **quantity > 0)
+            .map(|(content, quantity)| format!("{:?}: {}", content, quantity))
+            .collect();
+        backpack.sort();
+        format!("ROBOT\n- STATE: {:?}\n- POSITION: {:?}\n- ENERGY: {}\n- BACKPACK: [{}]\n- SAVED: {}\n- GOAL: {:?}\n- BANKS: {} free, {} filled",
+            self.state,
+            self.current_position(),
+            self.energy_level(),
+            backpack.join(", "),
+            self.saved,
+            self.goal,
+            self.known_banks(BankStatus::Free).len(),
+            self.known_banks(BankStatus::Filled).len())
+    }
+    fn is_free_bank(&self, coord: (usize, usize)) -> bool {
+        if let Some(banks) = self.free_banks.get(&bank_key()) {
+            for (bank, _) in banks.iter() {
+                if bank.0 == coord.0 && bank.1 == coord.1 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+    fn is_filled_bank(&self, coord: (usize, usize)) -> bool {
+        if let Some(banks) = self.filled_banks.get(&bank_key()) {
+            for (bank, _) in banks.iter() {
+                if bank.0 == coord.0 && bank.1 == coord.1 {
+                    return true;
                 }
             }
         }
+        false
+    }
+    fn in_cooldown(&self, coord: (usize, usize)) -> bool {
+        match self.bank_cooldowns.get(&coord) {
+            Some(tick) => self.ticks < tick + self.config.filled_bank_cooldown,
+            None => false
+        }
+    }
+    // Keeps at most `max_used_banks` used banks, dropping the ones with less deposited
+    // and, among those, the ones not used for longer
+    // The most deposited bank and the one just used are always kept
+    fn evict_used_banks(&mut self, current: (usize, usize)) {
+        let top = self.best_used_bank(false);
+        while self.used_banks.len() > self.config.max_used_banks.max(1) {
+            let evicted = self.used_banks.iter()
+                .filter(|(coord, _)| Some(**coord) != top && **coord != current)
+                .min_by_key(|(coord, money)| (**money, self.used_banks_tick.get(*coord).cloned().unwrap_or(0), **coord))
+                .map(|(coord, _)| *coord);
+            match evicted {
+                Some(coord) => {
+                    println!("Forgetting the used bank at {:?}", coord);
+                    self.used_banks.remove(&coord);
+                    self.used_banks_tick.remove(&coord);
+                },
+                None => break
+            }
+        }
+    }
+    fn best_used_bank(&self, skip_cooling: bool) -> Option<(usize, usize)> {
+        let mut highest = 0;
+        let mut best = None;
+        for ((x, y), money) in self.used_banks.iter() {
+            if skip_cooling && self.in_cooldown((*x, *y)) {
+                continue;
+            }
+            if *money > highest {
+                highest = *money;
+                best = Some((*x, *y));
+            }
+        }
+        best
+    }
+    fn go_to_closest_used_bank(&mut self, world: &mut World) -> Option<Direction> {
+        // Recently visited filled banks are used only if there is nothing else
+        let best = self.best_used_bank(true).or(self.best_used_bank(false)).unwrap_or((0, 0));
+        self.go_next_to(world, best);
+        if self.is_filled_bank(best) {
+            self.bank_cooldowns.insert(best, self.ticks);
+        }
+        self.adjacent_bank_direction(world)
+    }
+    // Tile at the configured corner of the bank that is in the direction from the bot
+    fn approach_position(&self, direction: Direction) -> Option<(usize, usize)> {
+        let bank = step_from(self.current_position(), &direction)?;
+        offset_from(bank, self.config.approach_corner.offset())
+    }
+    fn finish(&mut self, world: &mut World) {
+        // The enclosure was started in a previous tick, it goes on around the same bank
+        if self.asphalt_done > 0 {
+            self.asphalt_around(world);
+            if self.asphalt_done == ASPHALT_PROJECTS {
+                self.set_state(State::Enjoying);
+            }
+            return;
+        }
+
+        // Go to the closest bank
+        let direction = self.go_to_closest_used_bank(world);
+
+        // Every asphalt project checks its own energy, see `asphalt_around`
+        if let Some(direction) = direction {
+           self.asphalt_bank = step_from(self.current_position(), &direction);
+           // Reach the configured corner of the bank
+           if let Some(corner) = self.approach_position(direction) {
+               self.reach_position(world, corner.0, corner.1);
+           }
+           // Surrond the bank with asphalt
+           self.asphalt_around(world);
+
+           // Go enjoy the thing, once the enclosure is complete
+           if self.asphalt_done == ASPHALT_PROJECTS {
+               self.set_state(State::Enjoying);
+           }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use robotics_lib::runner::Runner;
+    use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+    use robotics_lib::world::world_generator::Generator;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+    use crate::config::Corner;
+
+    // Square world of grass with the contents placed on it, the bot starts at `spawn`
+    struct TestWorld {
+        map: Vec<Vec<Tile>>,
+        spawn: (usize, usize),
+        weather: WeatherType,
+    }
+
+    impl TestWorld {
+        fn new(size: usize, spawn: (usize, usize)) -> Self {
+            let grass = Tile { tile_type: TileType::Grass, content: Content::None, elevation: 0 };
+            TestWorld { map: vec![vec![grass; size]; size], spawn, weather: WeatherType::Sunny }
+        }
+        fn with(mut self, coord: (usize, usize), content: Content) -> Self {
+            self.map[coord.0][coord.1].content = content;
+            self
+        }
+        fn with_type(mut self, coord: (usize, usize), tile_type: TileType) -> Self {
+            self.map[coord.0][coord.1].tile_type = tile_type;
+            self
+        }
+    }
+
+    impl Generator for TestWorld {
+        fn gen(&mut self) -> (Vec<Vec<Tile>>, (usize, usize), EnvironmentalConditions, f32, Option<HashMap<Content, f32>>) {
+            let conditions = EnvironmentalConditions::new(&[self.weather.clone()], 15, 12).unwrap();
+            (self.map.clone(), self.spawn, conditions, 100.0, None)
+        }
+    }
+
+    // Runnable that gives the bot and the world to the test at every tick
+    struct Driven<F: FnMut(&mut SaverBot, &mut World)> {
+        bot: SaverBot,
+        tick: F,
+    }
+
+    impl<F: FnMut(&mut SaverBot, &mut World)> Runnable for Driven<F> {
+        fn process_tick(&mut self, world: &mut World) {
+            (self.tick)(&mut self.bot, world);
+        }
+        fn handle_event(&mut self, event: Event) {
+            self.bot.handle_event(event);
+        }
+        fn get_energy(&self) -> &Energy {
+            self.bot.get_energy()
+        }
+        fn get_energy_mut(&mut self) -> &mut Energy {
+            self.bot.get_energy_mut()
+        }
+        fn get_backpack(&self) -> &BackPack {
+            self.bot.get_backpack()
+        }
+        fn get_backpack_mut(&mut self) -> &mut BackPack {
+            self.bot.get_backpack_mut()
+        }
+        fn get_coordinate(&self) -> &Coordinate {
+            self.bot.get_coordinate()
+        }
+        fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+            self.bot.get_coordinate_mut()
+        }
+    }
+
+    // Runs the ticks in the world, calling `tick` with the bot at every one of them
+    fn run(bot: SaverBot, mut world: TestWorld, ticks: usize, tick: impl FnMut(&mut SaverBot, &mut World) + 'static) {
+        let mut runner = Runner::new(Box::new(Driven { bot, tick }), &mut world).unwrap();
+        for _ in 0..ticks {
+            runner.game_tick().unwrap();
+        }
+    }
+
+    // Value shared between the test and the ticks
+    fn shared<T>(value: T) -> (Rc<RefCell<T>>, Rc<RefCell<T>>) {
+        let value = Rc::new(RefCell::new(value));
+        (value.clone(), value)
+    }
+
+    #[test]
+    fn legal_transition_succeeds() {
+        assert!(State::CoinCollecting.can_transition_to(&State::Saving));
+        let mut bot = SaverBot::new(None);
+        assert!(bot.set_state(State::Saving));
+        assert_eq!(bot.current_state(), State::Saving);
+    }
+
+    #[test]
+    fn illegal_transition_is_refused() {
+        assert!(!State::Enjoying.can_transition_to(&State::CoinCollecting));
+        assert!(!State::Saving.can_transition_to(&State::Trading));
+        // Refused and traced, the bot stays where it was
+        let mut bot = SaverBot::new(None);
+        bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+        assert!(!bot.set_state(State::BankSearching));
+        assert_eq!(bot.current_state(), State::CoinCollecting);
+        assert_eq!(bot.current_trace.unwrap().decisions, vec![String::from("Illegal transition from CoinCollecting to BankSearching")]);
+    }
+
+    #[test]
+    fn every_working_state_can_save_and_stop() {
+        for state in [State::CoinCollecting, State::RockCollecting, State::Trading, State::Saving, State::BankSearching, State::Finish] {
+            assert!(state.can_transition_to(&State::Saving), "{:?} can't start saving", state);
+            assert!(state.can_transition_to(&State::Enjoying), "{:?} can't stop", state);
+        }
+        // Carrying nothing, the shutdown stops the bot right away
+        let mut bot = SaverBot::new(None);
+        assert!(bot.set_state(State::Trading));
+        bot.begin_shutdown();
+        assert_eq!(bot.current_state(), State::Enjoying);
+    }
+
+    #[test]
+    fn muted_bot_skips_event_sounds() {
+        let mut bot = SaverBot::new(None);
+        bot.mute(true);
+        assert!(!bot.play_event_sound(&Event::Ready));
+        // Toggling the mute does not create (nor load) an audio tool
+        #[cfg(feature = "audio")]
+        assert!(bot.audio.is_none());
+        bot.mute(false);
+        #[cfg(feature = "audio")]
+        assert!(bot.play_event_sound(&Event::Ready));
+    }
+
+    #[test]
+    #[cfg(feature = "audio")]
+    fn muted_music_starts_once_unmuted() {
+        let silent = OxAgAudioTool::new(HashMap::new(), HashMap::new(), HashMap::new()).unwrap();
+        let mut bot = SaverBot::with_audio(None, silent);
+        bot.mute(true);
+        bot.play_music("assets/default/music.ogg", 1.0).unwrap();
+        assert!(!bot.music_playing);
+        bot.mute(false);
+        assert!(bot.music_playing);
+        // Muting again and back does not start a second loop
+        bot.mute(true);
+        bot.mute(false);
+        assert!(bot.music_playing);
+    }
+
+    #[test]
+    fn just_filled_bank_is_not_chosen_again() {
+        let mut bot = SaverBot::new(None);
+        bot.used_banks.insert((2, 2), 20);
+        bot.used_banks.insert((6, 6), 5);
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        bot.fill_bank((2, 2));
+        assert_eq!(bot.best_used_bank(true), Some((6, 6)));
+        // Once the cooldown is over the most used bank is the best again
+        bot.ticks += bot.config.filled_bank_cooldown;
+        assert_eq!(bot.best_used_bank(true), Some((2, 2)));
+    }
+
+    #[test]
+    fn injected_bank_is_where_the_bot_saves() {
+        let world = TestWorld::new(10, (0, 0))
+            .with((0, 1), Content::Coin(4))
+            .with((6, 6), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        assert!(bot.add_known_bank((6, 6), BankStatus::Free, None));
+        assert!(!bot.add_known_bank((6, 6), BankStatus::Free, None));
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            *seen.borrow_mut() = Some((manhattan(bot.current_position(), (6, 6)), bot.bank_accepted.get(&(6, 6)).cloned()));
+        });
+        // The bank never seen by the bot gets the coins
+        assert_eq!(*result.borrow(), Some((1, Some(4))));
+    }
+
+    #[test]
+    fn destroy_loop_stops_when_energy_runs_low() {
+        // The bank close by makes the bot destroy tile by tile instead of using the destroy zone
+        let world = TestWorld::new(9, (4, 4))
+            .with((2, 2), Content::Bank(0..20))
+            .with((3, 4), Content::Coin(1))
+            .with((5, 4), Content::Coin(1))
+            .with((4, 3), Content::Coin(1))
+            .with((4, 5), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        let (destroyed, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            // The first coin tells how much a destroy costs
+            let before = bot.energy_level();
+            destroy(bot, world, Direction::Up).unwrap();
+            let cost = before - bot.energy_level();
+            // Three coins left, the energy is enough only for two of them
+            bot.config.per_step_energy = bot.energy_level() + 1 - 2 * cost;
+            bot.destroy_area(world);
+            *seen.borrow_mut() = bot.destroyed.len();
+        });
+        assert_eq!(*destroyed.borrow(), 2);
+    }
+
+    #[test]
+    fn save_threshold_is_clamped_to_the_backpack() {
+        let mut bot = SaverBot::new(None);
+        bot.config.thresholds.insert(Content::Coin(0), Thresholds { save_at: Some(1000), ..Default::default() });
+        let size = bot.get_backpack().get_size();
+        assert_eq!(bot.thresholds(&Content::Coin(0)).save_at, Some(size));
+
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(size));
+        let (due, seen) = shared(false);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            *seen.borrow_mut() = bot.save_due();
+        });
+        assert!(*due.borrow());
+    }
+
+    #[test]
+    fn shutdown_banks_the_coins_before_being_done() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((3, 5), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 5), BankStatus::Free, None);
+        let (ticks, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 10, move |bot, world| {
+            if first {
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+                bot.begin_shutdown();
+                assert!(!bot.is_done());
+            }
+            bot.step(world);
+            seen.borrow_mut().push((bot.is_done(), bot.count_content(&Content::Coin(0)), bot.saved_count()));
+        });
+        let done = ticks.borrow().iter().find(|(done, _, _)| *done).cloned();
+        assert_eq!(done, Some((true, 0, 3)));
+    }
+
+    #[test]
+    fn rocks_are_banked_when_configured() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Rock(3))
+            .with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.bank_contents = vec![Content::Rock(0)];
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (result, seen) = shared((0, 0, 0));
+        let mut first = true;
+        run(bot, world, 4, move |bot, world| {
+            if first {
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+                assert!(bot.worth_saving());
+                bot.set_state(State::Saving);
+            }
+            bot.step(world);
+            let deposited = bot.deposited.get(&Content::Rock(0)).cloned().unwrap_or(0);
+            *seen.borrow_mut() = (deposited, bot.count_content(&Content::Rock(0)), bot.saved_count());
+        });
+        assert_eq!(*result.borrow(), (3, 0, 0));
+    }
+
+    #[test]
+    fn distance_to_the_nearer_of_two_banks() {
+        let mut bot = SaverBot::new(None);
+        assert_eq!(bot.distance_to_nearest_bank(), None);
+        bot.add_known_bank((9, 9), BankStatus::Free, None);
+        bot.add_known_bank((2, 5), BankStatus::Free, None);
+        bot.add_known_bank((2, 3), BankStatus::Filled, None);
+        assert_eq!(bot.distance_to_nearest_bank(), Some(7));
+    }
+
+    #[test]
+    fn teleport_drops_the_target_until_the_next_tick() {
+        // The way down to the bank goes over a teleport to the other side of the map
+        let world = TestWorld::new(10, (0, 0))
+            .with((0, 1), Content::Coin(4))
+            .with((6, 6), Content::Bank(0..20))
+            .with_type((3, 0), TileType::Teleport(true))
+            .with_type((9, 9), TileType::Teleport(true));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((6, 6), BankStatus::Free, None);
+        let (result, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 2, move |bot, world| {
+            bot.sync_position();
+            if first {
+                first = false;
+                destroy(bot, world, Direction::Right).unwrap();
+            }
+            bot.force_state(State::Saving);
+            bot.save(world);
+            seen.borrow_mut().push((bot.target, manhattan(bot.current_position(), (6, 6)), bot.bank_accepted.get(&(6, 6)).cloned()));
+        });
+        let result = result.borrow();
+        // Away from the bank after the jump, with no target left to walk toward
+        assert_eq!((result[0].0, result[0].2), (None, None));
+        assert!(result[0].1 > 1);
+        // The way to the bank is computed again from where the bot landed
+        assert_eq!(result[1], (None, 1, Some(4)));
+    }
+
+    #[test]
+    fn wander_reaches_at_most_the_configured_targets() {
+        let world = TestWorld::new(12, (4, 4));
+        let mut bot = SaverBot::new(None);
+        bot.config.max_wander_targets_per_tick = Some(2);
+        let (result, seen) = shared((0, (0, 0)));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            // Five found targets, the highest priority is reached first
+            let heap = BinaryHeap::from(vec![(5, (6, 6)), (4, (6, 8)), (3, (8, 6)), (2, (8, 8)), (1, (7, 7))]);
+            let visited = bot.reach_found_targets(world, heap);
+            *seen.borrow_mut() = (visited, bot.current_position());
+        });
+        // The bot stops at the second target, the others are left to the next search
+        assert_eq!(*result.borrow(), (2, (6, 8)));
+    }
+
+    #[test]
+    fn blocked_move_is_reported() {
+        let world = TestWorld::new(9, (4, 4)).with_type((4, 5), TileType::DeepWater);
+        let bot = SaverBot::new(None);
+        let (errors, seen) = shared(vec![]);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            assert!(!bot.try_go(world, Direction::Right));
+            *seen.borrow_mut() = bot.last_tick_errors().iter().map(|error| format!("{:?}", error)).collect();
+        });
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].starts_with("Go(Right"));
+    }
+
+    #[test]
+    fn exploration_heads_to_the_commanded_coordinate() {
+        let world = TestWorld::new(20, (2, 2));
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(16, 16);
+        let (distances, seen) = shared(vec![manhattan((2, 2), (16, 16))]);
+        run(bot, world, 3, move |bot, world| {
+            bot.sync_position();
+            bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+            seen.borrow_mut().push(manhattan(bot.current_position(), (16, 16)));
+        });
+        assert!(distances.borrow().windows(2).all(|pair| pair[1] < pair[0]));
+    }
+
+    #[test]
+    fn carried_trees_are_recycled_into_rocks() {
+        // No rock on the map, the only way to get them is recycling
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Tree(2));
+        let mut bot = SaverBot::new(None);
+        bot.force_state(State::RockCollecting);
+        let (rocks, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            assert!(bot.count_content(&Content::Tree(0)) > 0);
+            bot.rock_collect(world);
+            *seen.borrow_mut() = bot.count_content(&Content::Rock(0));
+        });
+        assert!(*rocks.borrow() > 0);
+    }
+
+    // Tiles the bot walked on in the traced actions, starting from `start`
+    fn walked(trace: Option<&TickTrace>, start: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut position = start;
+        let mut walked = vec![];
+        for action in trace.iter().flat_map(|trace| trace.actions.iter()) {
+            let direction = match action.as_str() {
+                "go Up: Ok(())" => Direction::Up,
+                "go Down: Ok(())" => Direction::Down,
+                "go Left: Ok(())" => Direction::Left,
+                "go Right: Ok(())" => Direction::Right,
+                _ => continue
+            };
+            position = step_from(position, &direction).unwrap();
+            walked.push(position);
+        }
+        walked
+    }
+
+    #[test]
+    fn lava_on_the_shortest_way_is_not_stepped_on() {
+        let world = TestWorld::new(9, (4, 4)).with_type((4, 5), TileType::Lava);
+        let bot = SaverBot::new(None);
+        let (result, seen) = shared((false, vec![], vec![]));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            let reached = bot.reach_position(world, 4, 8);
+            let errors = bot.last_tick_errors().iter().map(|error| format!("{:?}", error)).collect();
+            *seen.borrow_mut() = (reached, walked(bot.current_trace.as_ref(), (4, 4)), errors);
+        });
+        let (reached, positions, errors) = result.borrow().clone();
+        // The bot goes around the lava instead
+        assert!(reached);
+        assert_eq!(positions.last(), Some(&(4, 8)));
+        assert!(!positions.contains(&(4, 5)));
+        assert!(errors.iter().any(|error| error.starts_with("Unsafe(Right, Lava")));
+    }
+
+    #[test]
+    fn tick_budget_makes_the_bot_yield() {
+        let world = TestWorld::new(9, (4, 0));
+        let mut bot = SaverBot::new(None);
+        bot.config.max_tick_duration = Some(Duration::ZERO);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.tick_started = Some(Instant::now());
+            let reached = bot.reach_position(world, 4, 8);
+            *seen.borrow_mut() = Some((reached, bot.current_position(), bot.target, bot.current_state()));
+        });
+        // The bot stops right away but still knows where it was going
+        assert_eq!(*result.borrow(), Some((false, (4, 0), Some((4, 8)), State::CoinCollecting)));
+    }
+
+    #[test]
+    fn bank_found_is_notified_once() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        let (found, seen) = shared(vec![]);
+        bot.set_on_bank_found(move |coord| seen.borrow_mut().push(coord));
+        run(bot, world, 2, move |bot, world| {
+            bot.sync_position();
+            bot.look_for_unknown_banks(world);
+        });
+        assert_eq!(*found.borrow(), vec![(4, 5)]);
+    }
+
+    #[test]
+    fn reset_gives_a_fresh_bot_with_the_same_tools() {
+        let mut bot = SaverBot::new(Some(10));
+        #[cfg(feature = "audio")]
+        {
+            bot.audio = Some(OxAgAudioTool::new(HashMap::new(), HashMap::new(), HashMap::new()).unwrap());
+        }
+        bot.mute(true);
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        bot.saved = 7;
+        bot.ticks = 40;
+        assert!(bot.set_state(State::Saving));
+        bot.reset(Some(3));
+        assert_eq!(bot.current_state(), State::CoinCollecting);
+        assert_eq!((bot.saved_count(), bot.ticks, bot.goal), (0, 0, Some(3)));
+        assert!(bot.known_banks(BankStatus::Free).is_empty());
+        assert!(bot.muted);
+        #[cfg(feature = "audio")]
+        assert!(bot.audio.is_some());
+    }
+
+    #[test]
+    fn bot_can_start_searching_for_a_bank() {
+        assert!(SaverBot::with_initial_state(None, State::Enjoying).is_none());
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((1, 1), Content::Bank(0..20));
+        let mut bot = SaverBot::with_initial_state(None, State::BankSearching).unwrap();
+        bot.add_known_bank((1, 1), BankStatus::Free, None);
+        let (state, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            assert_eq!(bot.current_state(), State::BankSearching);
+            bot.step(world);
+            *seen.borrow_mut() = Some(bot.current_state());
+        });
+        // The search found the known bank and the bot goes saving
+        assert_eq!(*state.borrow(), Some(State::Saving));
+    }
+
+    #[test]
+    fn detour_makes_the_trip_longer_than_the_estimate() {
+        let world = TestWorld::new(9, (4, 4))
+            .with_type((4, 5), TileType::Lava)
+            .with((4, 8), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((4, 8), BankStatus::Free, None);
+        let (travel, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            assert!(bot.go_to_closest_open_bank(world).is_some());
+            *seen.borrow_mut() = bot.last_travel_error();
+        });
+        let (estimated, actual) = travel.borrow().unwrap();
+        assert_eq!(estimated, 4);
+        assert!(actual > estimated);
+    }
+
+    #[test]
+    fn trading_at_the_threshold_does_not_oscillate() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Garbage(5));
+        let mut bot = SaverBot::new(None);
+        bot.config.trade_cooldown_ticks = 5;
+        let (states, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 10, move |bot, world| {
+            if first {
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+                assert!(bot.trade_due());
+            }
+            bot.step(world);
+            seen.borrow_mut().push(bot.current_state());
+        });
+        // Two trades are at least the cooldown apart, whatever the trade left in the backpack
+        let trades: Vec<usize> = states.borrow().iter().enumerate()
+            .filter(|(_, state)| **state == State::Trading)
+            .map(|(tick, _)| tick)
+            .collect();
+        assert!(!trades.is_empty());
+        assert!(trades.windows(2).all(|pair| pair[1] - pair[0] >= 5));
+    }
+
+    #[test]
+    fn a_large_load_fills_all_the_small_banks() {
+        // The backpack holds 20, so the load is three banks of 5
+        let world = TestWorld::new(11, (4, 4))
+            .with((4, 5), Content::Coin(15))
+            .with((2, 4), Content::Bank(0..5))
+            .with((6, 4), Content::Bank(0..5))
+            .with((4, 8), Content::Bank(0..5));
+        let mut bot = SaverBot::new(None);
+        for bank in [(2, 4), (6, 4), (4, 8)] {
+            bot.add_known_bank(bank, BankStatus::Free, Some(5));
+        }
+        let (result, seen) = shared((0, 0));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            assert!(bot.set_state(State::Saving));
+            bot.save(world);
+            *seen.borrow_mut() = (bot.saved_count(), bot.known_banks(BankStatus::Filled).len());
+        });
+        assert_eq!(*result.borrow(), (15, 3));
+    }
 
-        if where_can_i_go.len() == 0 {
-            where_can_i_go.push(SearchDirection::BottomLeft);
-            where_can_i_go.push(SearchDirection::BottomRight);
-            where_can_i_go.push(SearchDirection::TopLeft);
-            where_can_i_go.push(SearchDirection::TopRight);
+    #[test]
+    fn describe_reports_the_state_and_the_saved_coins() {
+        let mut bot = SaverBot::new(Some(20));
+        bot.saved = 12;
+        assert!(bot.set_state(State::Saving));
+        let description = bot.describe();
+        assert!(description.contains("STATE: Saving"));
+        assert!(description.contains("SAVED: 12"));
+        // Nothing changes by describing the bot
+        assert_eq!(description, bot.describe());
+    }
+
+    #[test]
+    fn heavier_quadrant_is_explored_more() {
+        let mut bot = SaverBot::new(None);
+        bot.set_seed(7);
+        bot.config.quadrant_weights = Some([8.0, 1.0, 1.0, 1.0]);
+        let mut chosen = [0; 4];
+        for _ in 0..1000 {
+            chosen[quadrant_index(&bot.pick_quadrant(&DIRECTIONS))] += 1;
         }
+        assert!(chosen[0] > 600);
+        assert!(chosen[1..].iter().all(|count| *count < 200));
+    }
 
-        let res = st.look_for_this_content(self, world, contents.clone(),
-                2 , clone_direction(&where_can_i_go[rand::thread_rng().gen_range(0..where_can_i_go.len())]));
-        match res {
-            Ok(_) => {
-                // Save the banks into the map
-                if contents.contains(&Content::Bank(Range{start: 0, end: 0})) {
-                    for (_, coord) in st.found_content_coords.iter() {
-                        for (posx, posy) in coord {
-                            if let Some(coord) = self.free_banks.clone().get(&Content::Bank(Range { start: 0, end: 0 })) {
-                                for (coord, _) in coord {
-                                    if coord.0 != posx.clone() || coord.1 != posy.clone() {
-                                        self.free_banks.save(&Content::Bank(Range { start: 0, end: 0 }), &ChartedCoordinate(posx.clone(), posy.clone()));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }else {
-                    let mut heap = BinaryHeap::new();
-                    // Pupulate heap for closest stuff to current distance
-                    let (x, y) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
-                    for (_, coord) in st.found_content_coords.iter() {
-                        for (posx, posy) in coord {
-                            let dist = (posx.clone() as isize - x as isize).abs() + (posy.clone() as isize - y as isize).abs();
-                            heap.push((dist, (posx.clone(), posy.clone())));
-                        }
-                    }
+    #[test]
+    fn empty_deposit_keeps_the_bank_free() {
+        let world = TestWorld::new(9, (4, 4)).with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        // Shutting down the bot goes to the bank even with nothing to deposit
+        bot.shutting_down = true;
+        bot.force_state(State::Saving);
+        let (banks, seen) = shared((vec![], vec![]));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.save(world);
+            *seen.borrow_mut() = (bot.known_banks(BankStatus::Free), bot.known_banks(BankStatus::Filled));
+        });
+        assert_eq!(*banks.borrow(), (vec![(3, 4)], vec![]));
+    }
 
-                    while self.get_energy().has_enough_energy(400) && heap.len() > 0 {
-                        let (_, (x, y)) = heap.pop().unwrap();
-                        let _ = self.reach_position(world, x, y);
-                        self.destroy_area(world);
-                    }
-                }
-            },
-            Err(e) => println!("Error: {:?}", e)
+    fn tile(content: Content) -> Tile {
+        Tile { tile_type: TileType::Grass, content, elevation: 0 }
+    }
+
+    #[test]
+    fn radius_query_returns_only_the_matches_inside() {
+        let mut bot = SaverBot::new(None);
+        // A cluster of coins around (20, 20), one far away and a rock inside
+        for coord in [(20, 20), (19, 20), (20, 22), (23, 20), (40, 40)] {
+            bot.record_seen(coord, tile(Content::Coin(1)));
         }
-        for _ in 0..4 {
-            let _ = go(self, world, [Direction::Up, Direction::Down, Direction::Left, Direction::Right][rand::thread_rng().gen_range(0..4)].clone());
+        bot.record_seen((20, 21), tile(Content::Rock(1)));
+        let mut found: Vec<(usize, usize)> = bot.seen_in_radius((20, 20), 2, |tile| matches!(tile.content, Content::Coin(_)))
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![(19, 20), (20, 20), (20, 22)]);
+    }
+
+    #[test]
+    fn unreachable_goal_is_given_up_after_the_deadline() {
+        let world = TestWorld::new(9, (4, 4));
+        let mut bot = SaverBot::new(Some(1000));
+        bot.config.goal_deadline_ticks = Some(3);
+        let (states, seen) = shared(vec![]);
+        run(bot, world, 5, move |bot, world| {
+            bot.step(world);
+            seen.borrow_mut().push(bot.current_state());
+        });
+        let states = states.borrow();
+        assert_eq!(states[0], State::CoinCollecting);
+        assert_eq!(states.last(), Some(&State::RockCollecting));
+    }
+
+    #[test]
+    fn bank_capacity_is_learned_from_the_deposits() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((3, 4), Content::Bank(0..5))
+            .with((4, 5), Content::Coin(3))
+            .with((5, 4), Content::Coin(4));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (result, seen) = shared(vec![]);
+        let mut piles = vec![Direction::Down, Direction::Right];
+        run(bot, world, 2, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, piles.pop().unwrap()).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            let accepted = bot.bank_accepted.get(&(3, 4)).cloned().unwrap_or(0);
+            seen.borrow_mut().push((accepted, bot.learned_capacity((3, 4)), bot.known_banks(BankStatus::Filled)));
+        });
+        // The second deposit is accepted only in part, so the bank is full with 5
+        assert_eq!(*result.borrow(), vec![(3, None, vec![]), (5, Some(5), vec![(3, 4)])]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "audio"))]
+    fn ticks_run_without_audio() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(1));
+        let bot = SaverBot::new(None);
+        let (ticks, seen) = shared(0);
+        run(bot, world, 3, move |bot, world| {
+            bot.step(world);
+            assert!(!bot.play_event_sound(&Event::Ready));
+            *seen.borrow_mut() = bot.ticks;
+        });
+        assert_eq!(*ticks.borrow(), 3);
+    }
+
+    #[test]
+    fn coverage_is_the_fraction_of_seen_tiles() {
+        let mut bot = SaverBot::new(None);
+        assert_eq!(bot.coverage((10, 10)), 0.0);
+        for row in 0..5 {
+            for col in 0..5 {
+                bot.record_seen((row, col), tile(Content::None));
+            }
         }
-        
+        // Seen again, and outside of the world: neither counts
+        bot.record_seen((0, 0), tile(Content::Coin(1)));
+        bot.record_seen((12, 3), tile(Content::None));
+        assert_eq!(bot.coverage((10, 10)), 0.25);
     }
-    fn closest_bank(&mut self) -> (usize, usize) {
-        let mut closest = (0, 0);
-        let mut distance = 1000;
-        let robot_x = self.get_coordinate().get_row();
-        let robot_y = self.get_coordinate().get_col();
 
-        if let Some(bank) = self.free_banks.get(&Content::Bank(Range{start: 0, end: 0})) {
-            for (coord, _) in bank.iter() {
+    #[test]
+    fn raised_goal_sends_the_bot_back_collecting_coins() {
+        let world = TestWorld::new(9, (4, 4));
+        let mut bot = SaverBot::new(Some(5));
+        bot.saved = 5;
+        bot.force_state(State::RockCollecting);
+        bot.set_goal(Some(10));
+        assert_eq!(bot.current_state(), State::CoinCollecting);
+        let (state, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.step(world);
+            *seen.borrow_mut() = Some(bot.current_state());
+        });
+        assert_eq!(*state.borrow(), Some(State::CoinCollecting));
+    }
+
+    #[test]
+    fn farther_coin_is_visited_before_the_near_garbage() {
+        let world = TestWorld::new(12, (2, 2));
+        let mut bot = SaverBot::new(None);
+        bot.config.max_wander_targets_per_tick = Some(1);
+        // The garbage is 4 steps away, the coin 7
+        let garbage = (bot.target_score(&Content::Garbage(1), manhattan((2, 2), (4, 4))), (4, 4));
+        let coin = (bot.target_score(&Content::Coin(1), manhattan((2, 2), (5, 6))), (5, 6));
+        let (position, seen) = shared((0, 0));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.reach_found_targets(world, BinaryHeap::from(vec![garbage, coin]));
+            *seen.borrow_mut() = bot.current_position();
+        });
+        assert_eq!(*position.borrow(), (5, 6));
+    }
+
+    #[test]
+    fn step_callback_runs_after_every_action() {
+        let world = TestWorld::new(9, (4, 4));
+        let mut bot = SaverBot::new(None);
+        let (calls, counted) = shared(0);
+        bot.set_step_callback(move || *counted.borrow_mut() += 1);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            // A destroy zone for each of the three contents looked for, two steps and a put
+            bot.destroy_area(world);
+            bot.try_go(world, Direction::Down);
+            bot.try_go(world, Direction::Right);
+            bot.try_put(world, Content::Coin(0), 1, Direction::Up);
+        });
+        assert_eq!(*calls.borrow(), 6);
+    }
+
+    #[test]
+    fn bots_sharing_the_reservations_pick_different_banks() {
+        let reservations = Arc::new(Mutex::new(HashSet::new()));
+        let mut bots: Vec<SaverBot> = (0..2).map(|_| SaverBot::new(None)).collect();
+        for bot in bots.iter_mut() {
+            bot.set_reservations(reservations.clone());
+            bot.add_known_bank((4, 6), BankStatus::Free, None);
+            bot.add_known_bank((4, 9), BankStatus::Free, None);
+        }
+        let first = bots[0].closest_bank();
+        assert!(bots[0].reserve_bank(first));
+        let second = bots[1].closest_bank();
+        assert_eq!((first, second), ((4, 6), (4, 9)));
+        assert!(!bots[1].reserve_bank(first));
+        bots[0].release_bank(first);
+        assert_eq!(bots[1].closest_bank(), (4, 6));
+    }
+
+    #[test]
+    fn reservation_is_released_when_the_bank_is_not_reached() {
+        let world = TestWorld::new(12, (4, 4))
+            .with((4, 5), Content::Coin(2))
+            .with((4, 9), Content::Bank(0..20));
+        let reservations = Arc::new(Mutex::new(HashSet::new()));
+        let mut bot = SaverBot::new(None);
+        bot.set_reservations(reservations.clone());
+        bot.add_known_bank((4, 9), BankStatus::Free, None);
+        bot.config.max_tick_duration = Some(Duration::ZERO);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.set_state(State::Saving);
+            // No time left for the trip, the bot doesn't get to the bank
+            bot.tick_started = Some(Instant::now());
+            bot.save(world);
+            assert_eq!(bot.current_state(), State::BankSearching);
+            assert!(bot.reserved.is_empty());
+        });
+        assert!(reservations.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn trace_records_the_state_and_the_actions() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.config.trace = true;
+        let (trace, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            assert!(bot.last_trace().is_none());
+            bot.step(world);
+            *seen.borrow_mut() = bot.last_trace().cloned();
+        });
+        let trace = trace.borrow().clone().unwrap();
+        assert_eq!((trace.tick, trace.state), (1, State::CoinCollecting));
+        assert!(!trace.actions.is_empty());
+    }
 
-                let dist = (coord.0 as isize - robot_x as isize).abs() + (coord.1 as isize - robot_y as isize).abs();
+    #[test]
+    fn tiles_are_destroyed_once_per_tick() {
+        // The bank close by makes the bot destroy tile by tile, the diagonal coins can't be reached
+        let world = TestWorld::new(9, (4, 4))
+            .with((2, 2), Content::Bank(0..20))
+            .with((3, 4), Content::Coin(1))
+            .with((5, 4), Content::Coin(1))
+            .with((4, 3), Content::Coin(1))
+            .with((4, 5), Content::Coin(1))
+            .with((5, 5), Content::Coin(1))
+            .with((3, 5), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        let (result, seen) = shared((vec![], 0));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            bot.destroy_area(world);
+            bot.destroy_area(world);
+            let mut destroyed: Vec<(usize, usize)> = bot.destroyed.iter().cloned().collect();
+            destroyed.sort();
+            let calls = bot.current_trace.as_ref().unwrap().actions.iter().filter(|action| action.starts_with("destroy ")).count();
+            *seen.borrow_mut() = (destroyed, calls);
+        });
+        assert_eq!(*result.borrow(), (vec![(3, 4), (4, 3), (4, 5), (5, 4)], 4));
+    }
+
+    #[test]
+    fn goal_goes_straight_to_finish_without_the_rock_phase() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(Some(3));
+        bot.config.skip_rock_phase = true;
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            *seen.borrow_mut() = Some((bot.current_state(), bot.count_content(&Content::Rock(0))));
+        });
+        assert_eq!(*result.borrow(), Some((State::Finish, 0)));
+    }
 
-                if dist < distance {
-                    distance = dist;
-                    closest = (coord.0, coord.1);
+    #[test]
+    fn reachable_bank_is_preferred_to_a_walled_one() {
+        let world = TestWorld::new(10, (5, 2));
+        let mut bot = SaverBot::new(None);
+        // Only the path counts, not the hazard around the bank
+        bot.config.bank_hazard_penalty = 0;
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        bot.add_known_bank((8, 8), BankStatus::Free, None);
+        let (chosen, seen) = shared(None);
+        run(bot, world, 1, move |bot, _| {
+            bot.sync_position();
+            for row in 0..10 {
+                for col in 0..10 {
+                    bot.record_seen((row, col), tile(Content::None));
                 }
             }
-        }
-        closest
+            for wall in [(1, 2), (3, 2), (2, 1), (2, 3)] {
+                bot.record_seen(wall, Tile { tile_type: TileType::Wall, content: Content::None, elevation: 0 });
+            }
+            *seen.borrow_mut() = Some(bot.closest_bank());
+        });
+        assert_eq!(*chosen.borrow(), Some((8, 8)));
     }
-    fn save(&mut self, world: &mut World) {
-        println!("Saving");
-        let (cx, cy) = self.closest_bank();
-        let (x, y) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
-         
-        let mut direction = self.go_to_closest_open_bank(world);
 
-        if (cx == x) && (cy == y) {
-            let res = go(self, world, Direction::Left);
-            match res {
-                Ok(_) => {direction = Some(Direction::Left);},
-                Err(_) => {
-                    let res = go(self, world, Direction::Right); 
-                    
-                    match res {Ok(_) => {direction = Some(Direction::Right);}, Err(_) => {
-                        let res = go(self, world, Direction::Up);
-                        
-                        match res {Ok(_) => {direction = Some(Direction::Up);}, Err(_) => {
-                            let _ = go(self, world, Direction::Down);
-                            direction = Some(Direction::Down);
-                        }}
-                    }}}
+    #[test]
+    #[cfg(feature = "audio")]
+    fn pickup_sound_is_added_to_the_event_map() {
+        let mut bot = SaverBot::new(None);
+        bot.set_pickup_sound(Content::Coin(3), "assets/default/event/event_ready.ogg");
+        bot.set_pickup_sound(Content::Fish(0), "assets/default/event/event_ready.ogg");
+        assert_eq!(bot.pickup_sounds.get(&Content::Coin(0)).map(|path| path.as_str()), Some("assets/default/event/event_ready.ogg"));
+        let events = SaverBot::audio_events(1.0, &bot.pickup_sounds);
+        // The content with a sound of its own is mapped, the others keep the default one
+        assert!(events.contains_key(&Event::AddedToBackpack(Content::Fish(0), 1)));
+        assert!(events.contains_key(&Event::AddedToBackpack(Content::Rock(0), 1)));
+    }
+
+    #[test]
+    fn bot_waits_for_the_world_to_be_ready() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(1));
+        let bot = SaverBot::new(None);
+        let (ticks, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 2, move |bot, world| {
+            if first {
+                first = false;
+                bot.ready = false;
+            } else {
+                bot.handle_event(Event::Ready);
             }
-        }
-        if let Some(dir) = direction {
-            let putting = put(self, world, Content::Coin(0), self.get_backpack().get_contents().get(&Content::Coin(0)).unwrap().clone(), dir);
-            match putting {
-                Ok(quantity) => {
-                    if quantity == 0 {
-                        let _ = self.free_banks.remove(&Content::Bank(Range { start: 0, end: 0 }), ChartedCoordinate(cx, cy));
-                        self.filled_banks.save(&Content::Bank(Range { start: 0, end: 0 }), &ChartedCoordinate(cx, cy));
-                    }
-                    self.saved += quantity;
-                    println!("Saved {quantity} coins");
+            bot.process_tick(world);
+            seen.borrow_mut().push((bot.idle_reason(), bot.current_position(), bot.count_content(&Content::Coin(0))));
+        });
+        let ticks = ticks.borrow();
+        assert_eq!(ticks[0], (Some(IdleReason::NotReady), (4, 4), 0));
+        assert_ne!(ticks[1].0, Some(IdleReason::NotReady));
+        assert_eq!(ticks[1].2, 1);
+    }
 
-                    // Update the seen banks in the hashmap
-                    let (x, y) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
-                    let mut value = 0;
-                    if let Some(coins) = self.used_banks.get(&(x, y)) {
-                        value = coins.clone();
-                    }
-                    self.used_banks.insert((x, y), value + quantity);
+    #[test]
+    fn bot_never_leaves_its_region() {
+        let world = TestWorld::new(16, (6, 6))
+            .with((2, 2), Content::Coin(1))
+            .with((7, 7), Content::Coin(1))
+            .with((12, 12), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.set_seed(3);
+        bot.config.trace = true;
+        bot.set_region((4, 4), (8, 8));
+        let (positions, seen) = shared(vec![]);
+        run(bot, world, 15, move |bot, world| {
+            bot.sync_position();
+            let start = bot.current_position();
+            bot.step(world);
+            seen.borrow_mut().extend(walked(bot.last_trace(), start));
+        });
+        assert!(!positions.borrow().is_empty());
+        assert!(positions.borrow().iter().all(|(row, col)| (4..=8).contains(row) && (4..=8).contains(col)));
+    }
 
-                    if let Some(goal) = self.goal {
-                        if self.saved >= goal {
-                            self.set_state(State::RockCollecting);
-                        }else {
-                            self.set_state(State::CoinCollecting);  
-                        }
-                    }else {
-                        self.set_state(State::CoinCollecting);
-                    }
-                },
-                Err(error) => println!("While saving there has been an issue {:?}", error)
+    #[test]
+    fn full_backpack_with_all_banks_filled_escapes_saving() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(20));
+        let mut bot = SaverBot::new(None);
+        bot.config.bank_search_deadline = 3;
+        bot.add_known_bank((1, 1), BankStatus::Filled, None);
+        let (states, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 8, move |bot, world| {
+            if first {
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+                assert!(bot.backpack_full());
+                bot.set_state(State::Saving);
             }
-        } else {
-            if let Some(goal) = self.goal {
-                if self.saved >= goal {
-                    self.set_state(State::RockCollecting);
-                }else {
-                    self.set_state(State::BankSearching);
-                }
-            }else {
-                self.set_state(State::BankSearching);
+            bot.step(world);
+            seen.borrow_mut().push(bot.current_state());
+        });
+        assert_eq!(states.borrow().last(), Some(&State::RockCollecting));
+    }
+
+    #[test]
+    fn exported_map_spans_the_seen_tiles() {
+        let mut bot = SaverBot::new(None);
+        assert!(bot.export_map().is_empty());
+        bot.record_seen((3, 5), tile(Content::None));
+        bot.record_seen((5, 4), Tile { tile_type: TileType::Sand, content: Content::None, elevation: 0 });
+        let map = bot.export_map();
+        // Rows 3 to 5 and columns 4 to 5
+        assert_eq!((map.len(), map[0].len()), (3, 2));
+        assert_eq!(map[0][1], Some(TileType::Grass));
+        assert_eq!(map[2][0], Some(TileType::Sand));
+        assert_eq!(map.iter().flatten().filter(|cell| cell.is_some()).count(), 2);
+    }
+
+    #[test]
+    fn bank_search_widens_until_the_bank_is_found() {
+        let world = TestWorld::new(30, (5, 5))
+            .with((5, 6), Content::Coin(3))
+            .with((5, 25), Content::Bank(0..20));
+        let mut bot = SaverBot::with_initial_state(None, State::BankSearching).unwrap();
+        bot.config.bank_search_retry_limit = 2;
+        bot.explore_toward(5, 29);
+        let (result, seen) = shared((0, vec![]));
+        let mut first = true;
+        run(bot, world, 12, move |bot, world| {
+            if first {
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+            }
+            bot.step(world);
+            let mut seen = seen.borrow_mut();
+            seen.0 = seen.0.max(bot.search_radius);
+            seen.1 = bot.known_banks(BankStatus::Free);
+        });
+        let (widest, banks) = result.borrow().clone();
+        assert!(widest > SaverConfig::default().search_radius);
+        assert_eq!(banks, vec![(5, 25)]);
+    }
+
+    #[test]
+    fn energy_level_follows_the_tick() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(1));
+        let bot = SaverBot::new(None);
+        let (levels, seen) = shared((0, 0, 0));
+        run(bot, world, 1, move |bot, world| {
+            let before = bot.energy_level();
+            bot.step(world);
+            *seen.borrow_mut() = (before, bot.energy_level(), bot.get_energy().get_energy_level());
+        });
+        let (before, after, energy) = *levels.borrow();
+        // Destroying the coin costs energy
+        assert!(after < before);
+        assert_eq!(after, energy);
+    }
+
+    #[test]
+    fn full_backpack_is_saved_before_destroying_more() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((4, 3), Content::Garbage(17))
+            .with((3, 4), Content::Coin(5));
+        let bot = SaverBot::new(None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            destroy(bot, world, Direction::Left).unwrap();
+            assert!(bot.backpack_full());
+            // Enough garbage to trade too, but the bot is already going to save
+            assert!(bot.trade_due());
+            bot.coin_collect(world);
+            *seen.borrow_mut() = Some((bot.current_state(), bot.count_content(&Content::Coin(0))));
+        });
+        assert_eq!(*result.borrow(), Some((State::Saving, 3)));
+    }
+
+    #[test]
+    fn go_to_nearest_lands_next_to_the_coin() {
+        let world = TestWorld::new(12, (2, 2)).with((7, 9), Content::Coin(2));
+        let mut bot = SaverBot::new(None);
+        bot.record_seen((7, 9), tile(Content::Coin(2)));
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            let arrived = bot.go_to_nearest(world, Content::Coin(0));
+            *seen.borrow_mut() = Some((arrived, manhattan(bot.current_position(), (7, 9))));
+        });
+        assert_eq!(*result.borrow(), Some((true, 1)));
+    }
+
+    #[test]
+    fn equidistant_banks_are_chosen_by_coordinate() {
+        let chosen: Vec<(usize, usize)> = [[(4, 0), (0, 4)], [(0, 4), (4, 0)]].iter().map(|banks| {
+            let mut bot = SaverBot::new(None);
+            for bank in banks {
+                bot.add_known_bank(*bank, BankStatus::Free, None);
+            }
+            bot.closest_bank()
+        }).collect();
+        assert_eq!(chosen, vec![(0, 4), (0, 4)]);
+    }
+
+    #[test]
+    fn small_load_does_not_start_a_save_trip() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(2))
+            .with((2, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(Some(100));
+        bot.config.min_save_load = 5;
+        // The goal is already over, the bot would bank what it has if worth it
+        bot.config.goal_deadline_ticks = Some(0);
+        bot.add_known_bank((2, 4), BankStatus::Free, None);
+        let (state, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            assert!(!bot.worth_saving());
+            bot.coin_collect(world);
+            *seen.borrow_mut() = Some(bot.current_state());
+        });
+        assert_eq!(*state.borrow(), Some(State::RockCollecting));
+    }
+
+    #[test]
+    fn seen_iterator_yields_the_recorded_tiles() {
+        let mut bot = SaverBot::new(None);
+        bot.record_seen((1, 2), tile(Content::None));
+        bot.record_seen((3, 4), tile(Content::Coin(1)));
+        bot.record_seen((1, 2), tile(Content::Rock(1)));
+        let mut tiles: Vec<((usize, usize), Content)> = bot.seen_iter().map(|(coord, tile)| (*coord, tile.content.clone())).collect();
+        tiles.sort_by_key(|(coord, _)| *coord);
+        assert_eq!(tiles, vec![((1, 2), Content::Rock(1)), ((3, 4), Content::Coin(1))]);
+    }
+
+    #[test]
+    fn corner_neighborhood_is_seen_without_wrapping() {
+        let world = TestWorld::new(9, (0, 0));
+        let bot = SaverBot::new(None);
+        let (result, seen) = shared((vec![], false));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.record_neighborhood(world);
+            let mut coords: Vec<(usize, usize)> = bot.seen_iter().map(|(coord, _)| *coord).collect();
+            coords.sort();
+            *seen.borrow_mut() = (coords, bot.check_if_seen(usize::MAX, usize::MAX));
+        });
+        assert_eq!(*result.borrow(), (vec![(0, 0), (0, 1), (1, 0), (1, 1)], false));
+    }
+
+    // Coins and garbage collected by one destroy of the area with the strategy, with little energy left
+    fn destroyed_with(strategy: DestroyStrategy) -> (usize, usize) {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(1))
+            .with((4, 3), Content::Garbage(1));
+        let mut bot = SaverBot::new(None);
+        bot.config.destroy_strategy = strategy;
+        let (result, seen) = shared((0, 0));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.config.conservative_energy_margin = bot.energy_level() + 1;
+            bot.destroy_area(world);
+            *seen.borrow_mut() = (bot.count_content(&Content::Coin(0)), bot.count_content(&Content::Garbage(0)));
+        });
+        let result = *result.borrow();
+        result
+    }
+
+    #[test]
+    fn greedy_strategy_destroys_everything() {
+        assert_eq!(destroyed_with(DestroyStrategy::Greedy), (1, 1));
+    }
+
+    #[test]
+    fn conservative_strategy_skips_the_low_value_contents() {
+        assert_eq!(destroyed_with(DestroyStrategy::Conservative), (1, 0));
+    }
+
+    #[test]
+    fn full_bank_sends_the_rest_to_the_next_bank() {
+        // The closest bank has room only for one of the coins
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((3, 4), Content::Bank(0..1))
+            .with((6, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        bot.add_known_bank((6, 4), BankStatus::Free, None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            *seen.borrow_mut() = Some((bot.saved_count(), bot.bank_accepted.get(&(6, 4)).cloned(), bot.known_banks(BankStatus::Filled)));
+        });
+        assert_eq!(*result.borrow(), Some((3, Some(2), vec![(3, 4)])));
+    }
+
+    // Contents searched by a wander from (4, 4) toward the bottom right, in order
+    fn searched_in(world: TestWorld) -> Vec<String> {
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(8, 8);
+        let (searches, seen) = shared(vec![]);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            bot.wander_in_seach_of(world, vec![Content::Garbage(0), Content::Rock(0), Content::Coin(0)]);
+            *seen.borrow_mut() = bot.current_trace.take().unwrap().decisions.into_iter()
+                .filter(|decision| decision.starts_with("Searching for"))
+                .collect();
+        });
+        let searches = searches.borrow().clone();
+        searches
+    }
+
+    #[test]
+    fn most_valuable_content_is_searched_first() {
+        let searches = searched_in(TestWorld::new(12, (4, 4)).with((6, 6), Content::Garbage(1)));
+        assert_eq!(searches, vec!["Searching for Coin(0)", "Searching for Rock(0)", "Searching for Garbage(0)"]);
+        // A coin found stops the search
+        let searches = searched_in(TestWorld::new(12, (4, 4)).with((6, 6), Content::Coin(1)));
+        assert_eq!(searches, vec!["Searching for Coin(0)"]);
+    }
+
+    #[test]
+    fn coins_are_not_put_where_there_is_no_bank() {
+        // The bank the bot knows of is not in the world
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(3));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.set_state(State::Saving);
+            bot.current_trace = Some(TickTrace::new(0, State::Saving));
+            bot.save(world);
+            let puts = bot.current_trace.as_ref().unwrap().actions.iter().filter(|action| action.starts_with("put")).count();
+            *seen.borrow_mut() = Some((puts, bot.count_content(&Content::Coin(0)), bot.current_state()));
+        });
+        assert_eq!(*result.borrow(), Some((0, 3, State::BankSearching)));
+    }
+
+    #[test]
+    fn recharge_makes_the_bot_act_again() {
+        let world = TestWorld::new(9, (4, 4));
+        let bot = SaverBot::new(None);
+        let (reasons, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 2, move |bot, world| {
+            if first {
+                first = false;
+                let gate = bot.energy_level() + 1;
+                bot.config.state_energy.insert(State::CoinCollecting, gate);
+                bot.step(world);
+                assert!(bot.waiting_for_energy);
+                seen.borrow_mut().push(bot.idle_reason());
+                // The energy is back
+                bot.config.state_energy.insert(State::CoinCollecting, 0);
+                bot.handle_event(Event::EnergyRecharged(10));
+                assert!(!bot.waiting_for_energy);
+            } else {
+                bot.step(world);
+                seen.borrow_mut().push(bot.idle_reason());
             }
+        });
+        assert_eq!(*reasons.borrow(), vec![Some(IdleReason::LowEnergy), None]);
+    }
+
+    #[test]
+    fn lawnmower_sweeps_the_rows_in_order() {
+        let mut bot = SaverBot::new(None);
+        bot.config.exploration_pattern = ExplorationPattern::Lawnmower;
+        bot.config.pattern_spacing = 2;
+        bot.config.pattern_extent = 4;
+        let mut waypoints = vec![];
+        for step in 0..6 {
+            bot.pattern_step = step;
+            waypoints.push(bot.pattern_waypoint().unwrap());
         }
+        assert_eq!(waypoints, vec![(0, 0), (0, 4), (2, 4), (2, 0), (4, 0), (4, 4)]);
+        // Then it starts again from the top
+        bot.pattern_step = 6;
+        assert_eq!(bot.pattern_waypoint(), Some((0, 0)));
     }
-    fn asphalt_around(&mut self, world: &mut World) {
-        // Asphaltinator tool used here
-        let mut asphaltinator = Asphaltinator::new();
-        let shape1 = Shape::Rectangle(3, 1);
-        let shape2 = Shape::Rectangle(1, 2);
-        let shape3 = Shape::Rectangle(2, 1);
-        let shape4 = Shape::Rectangle(1, 2);
-
-        let p1 = asphaltinator.design_project(shape1);
-        let p2 = asphaltinator.design_project(shape2);
-        let p3 = asphaltinator.design_project(shape3);
-        let p4 = asphaltinator.design_project(shape4);
-        let projects = vec![p1, p2, p3, p4];
-        for project in projects {
-            match project {
-                Ok(project) => {
-                    let _ = asphaltinator.asfalting(self, world, project);
-                },
-                Err(error) => println!("While asphaltinating there has been an issue {:?}", error)
+
+    #[test]
+    fn bank_marked_filled_is_not_chosen() {
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((4, 5), BankStatus::Free, None);
+        bot.add_known_bank((8, 8), BankStatus::Free, None);
+        assert_eq!(bot.closest_bank(), (4, 5));
+        assert!(bot.mark_bank_filled((4, 5)));
+        assert!(!bot.mark_bank_filled((4, 5)));
+        assert_eq!(bot.closest_bank(), (8, 8));
+        assert_eq!(bot.known_banks(BankStatus::Filled), vec![(4, 5)]);
+    }
+
+    // Builds the asphalt from the middle of the map carrying the rocks, the estimate of a tile
+    // comes from the energy left. Returns the projects built, the energy they took and the state after
+    fn asphalt_with(rocks: usize, tile_energy: impl Fn(usize) -> usize + 'static) -> (usize, usize, State) {
+        let world = TestWorld::new(12, (5, 5))
+            .with((5, 6), Content::Rock(rocks))
+            .with((4, 6), Content::Bank(0..20));
+        let mut bot = SaverBot::new(Some(0));
+        bot.force_state(State::Finish);
+        bot.asphalt_bank = Some((4, 6));
+        let (result, seen) = shared((0, 0, State::Finish));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            bot.config.asphalt_tile_energy = tile_energy(bot.energy_level());
+            let before = bot.energy_level();
+            let built = bot.asphalt_around(world);
+            let spent = before - bot.energy_level();
+            // Going on with the enclosure, nothing else fits
+            bot.finish(world);
+            *seen.borrow_mut() = (built, spent, bot.current_state());
+        });
+        let result = *result.borrow();
+        result
+    }
+
+    #[test]
+    fn asphalt_builds_only_the_projects_that_fit() {
+        // Rocks for the first two projects only, to know how much energy they take
+        let (built, spent, _) = asphalt_with(5, |_| 0);
+        assert_eq!(built, 2);
+        // Plenty of rocks, but the energy is estimated enough for the first two projects only
+        let (built, _, state) = asphalt_with(9, move |energy| (energy - spent) / 2 + 1);
+        assert_eq!((built, state), (2, State::Finish));
+    }
+
+    #[test]
+    fn raised_goal_restarts_the_enclosure() {
+        let mut bot = SaverBot::new(Some(5));
+        bot.saved = 5;
+        bot.force_state(State::Finish);
+        bot.asphalt_done = 2;
+        bot.set_goal(Some(10));
+        assert_eq!((bot.current_state(), bot.asphalt_done), (State::CoinCollecting, 0));
+    }
+
+    #[test]
+    fn destroying_stops_at_the_collection_cap() {
+        // The bank close by makes the bot destroy tile by tile
+        let world = TestWorld::new(9, (4, 4))
+            .with((2, 2), Content::Bank(0..20))
+            .with((3, 4), Content::Coin(1))
+            .with((5, 4), Content::Coin(1))
+            .with((4, 3), Content::Coin(1))
+            .with((4, 5), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((2, 2), BankStatus::Free, None);
+        bot.config.collection_caps.insert(Content::Coin(0), 2);
+        let (coins, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.destroy_area(world);
+            *seen.borrow_mut() = bot.count_content(&Content::Coin(0));
+        });
+        assert_eq!(*coins.borrow(), 2);
+    }
+
+    #[test]
+    fn step_runs_a_tick_of_the_bot() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Coin(1));
+        let bot = SaverBot::new(Some(1));
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.ready = false;
+            bot.step(world);
+            *seen.borrow_mut() = Some((bot.ticks, bot.idle_reason(), bot.current_state()));
+        });
+        // The coin is enough for the goal, so the bot goes saving it
+        assert_eq!(*result.borrow(), Some((1, None, State::Saving)));
+    }
+
+    #[test]
+    fn corner_neighborhood_is_handled_everywhere() {
+        let world = TestWorld::new(9, (0, 0))
+            .with((0, 1), Content::Bank(0..20))
+            .with((1, 0), Content::Coin(2));
+        let bot = SaverBot::new(None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.look_for_unknown_banks(world);
+            bot.record_neighborhood(world);
+            bot.destroy_area(world);
+            let adjacent = bot.scan_adjacent(world).iter().flatten().count();
+            let direction = bot.go_to_closest_open_bank(world);
+            *seen.borrow_mut() = Some((bot.known_banks(BankStatus::Free), bot.count_content(&Content::Coin(0)), adjacent, direction.is_some()));
+        });
+        // The coin touches the bank diagonally, so it is left there
+        assert_eq!(*result.borrow(), Some((vec![(0, 1)], 0, 2, true)));
+    }
+
+    #[test]
+    fn each_content_goes_to_the_bank_accepting_it() {
+        // Both banks are next to the bot, one takes only coins and the other only rocks
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((4, 3), Content::Rock(2))
+            .with((3, 4), Content::Bank(0..20))
+            .with((5, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.bank_contents = vec![Content::Coin(0), Content::Rock(0)];
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        bot.add_known_bank((5, 4), BankStatus::Free, None);
+        bot.set_bank_accepts((3, 4), vec![Content::Coin(0)]);
+        bot.set_bank_accepts((5, 4), vec![Content::Rock(0)]);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            destroy(bot, world, Direction::Left).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            bot.save(world);
+            *seen.borrow_mut() = Some((
+                bot.bank_accepted.get(&(3, 4)).cloned(),
+                bot.bank_accepted.get(&(5, 4)).cloned(),
+                bot.deposited.get(&Content::Coin(0)).cloned(),
+                bot.deposited.get(&Content::Rock(0)).cloned(),
+            ));
+        });
+        assert_eq!(*result.borrow(), Some((Some(3), Some(2), Some(3), Some(2))));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "asphalt projects built")]
+    fn inconsistent_state_fails_the_invariants() {
+        let mut bot = SaverBot::new(None);
+        bot.asphalt_done = ASPHALT_PROJECTS + 1;
+        bot.check_invariants();
+    }
+
+    // Whether the bot would go saving, with the trip target and a bank six tiles away
+    fn trip_ready_with(coins: usize) -> (bool, bool) {
+        let world = TestWorld::new(12, (4, 4))
+            .with((4, 5), Content::Coin(coins))
+            .with((10, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.target_trip_load = Some(6);
+        bot.add_known_bank((10, 4), BankStatus::Free, None);
+        let (ready, seen) = shared((false, false));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            *seen.borrow_mut() = (bot.trip_load_ready(), bot.save_due());
+        });
+        let ready = *ready.borrow();
+        ready
+    }
+
+    #[test]
+    fn trip_target_saves_before_the_threshold() {
+        // Six coins are enough with the bank close, far from the hard threshold
+        assert_eq!(trip_ready_with(6), (true, false));
+        assert_eq!(trip_ready_with(5), (false, false));
+    }
+
+    #[test]
+    fn cached_position_follows_the_moves() {
+        let world = TestWorld::new(9, (4, 4));
+        let (positions, seen) = shared(vec![]);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            bot.sync_position();
+            for direction in [Direction::Right, Direction::Down, Direction::Left] {
+                go(bot, world, direction).unwrap();
+                let coordinate = (bot.get_coordinate().get_row(), bot.get_coordinate().get_col());
+                seen.borrow_mut().push((bot.current_position(), coordinate));
             }
-        }   
+        });
+        assert_eq!(*positions.borrow(), vec![((4, 5), (4, 5)), ((5, 5), (5, 5)), ((5, 4), (5, 4))]);
     }
-    fn go_to_closest_used_bank(&mut self, world: &mut World) -> Option<Direction> {
-        let mut highest = 0;
-        let mut best = (0, 0);
-        for ((x, y), money) in self.used_banks.iter() {
-            if *money > highest {
-                highest = *money;
-                best = (*x, *y);
+
+    // Steps the bot has taken when the coins around the bank are all saved, if they are within the ticks
+    fn steps_to_bank_the_cluster(collect_and_bank: bool) -> Option<usize> {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((5, 4), Content::Coin(3))
+            .with((3, 4), Content::Coin(3))
+            .with((4, 2), Content::Bank(0..20))
+            .with((8, 8), Content::Coin(3));
+        let mut bot = SaverBot::new(None);
+        bot.config.collect_and_bank = collect_and_bank;
+        bot.add_known_bank((4, 2), BankStatus::Free, None);
+        bot.set_seed(5);
+        let (steps, seen) = shared(None);
+        run(bot, world, 15, move |bot, world| {
+            bot.step(world);
+            if seen.borrow().is_none() && bot.saved_count() >= 9 {
+                *seen.borrow_mut() = Some(bot.steps);
             }
+        });
+        let steps = *steps.borrow();
+        steps
+    }
+
+    #[test]
+    fn collecting_next_to_the_bank_saves_with_fewer_steps() {
+        let together = steps_to_bank_the_cluster(true);
+        let apart = steps_to_bank_the_cluster(false);
+        assert!(together.is_some());
+        // The separate phases wait for the hard threshold, away from the bank
+        assert!(apart.map_or(true, |apart| together.unwrap() < apart), "{:?} {:?}", together, apart);
+    }
+
+    #[test]
+    fn custom_thresholds_drive_the_decisions() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(3))
+            .with((4, 3), Content::Garbage(2));
+        let mut bot = SaverBot::new(None);
+        bot.config.thresholds.insert(Content::Coin(0), Thresholds { save_at: Some(3), ..Default::default() });
+        bot.config.thresholds.insert(Content::Garbage(0), Thresholds { trade_at: Some(2), ..Default::default() });
+        let (decisions, seen) = shared(vec![]);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            seen.borrow_mut().push((bot.save_due(), bot.trade_due()));
+            destroy(bot, world, Direction::Left).unwrap();
+            seen.borrow_mut().push((bot.save_due(), bot.trade_due()));
+        });
+        assert_eq!(*decisions.borrow(), vec![(true, false), (true, true)]);
+    }
+
+    #[test]
+    fn exhausted_energy_budget_stops_the_bot() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(1))
+            .with((5, 4), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.config.energy_budget = Some(1);
+        let (ticks, seen) = shared(vec![]);
+        run(bot, world, 3, move |bot, world| {
+            bot.step(world);
+            seen.borrow_mut().push((bot.total_energy_spent(), bot.current_position(), bot.current_state()));
+        });
+        let ticks = ticks.borrow();
+        assert!(ticks[0].0 >= 1);
+        assert_eq!(ticks[0].2, State::Enjoying);
+        // Nothing is spent nor done after the budget is over
+        assert_eq!(ticks[1], ticks[0]);
+        assert_eq!(ticks[2], ticks[0]);
+    }
+
+    #[test]
+    fn bank_with_a_range_is_found_by_the_standard_query() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Bank(3..9));
+        let (banks, seen) = shared(None);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.look_for_unknown_banks(world);
+            *seen.borrow_mut() = Some((bot.known_banks(BankStatus::Free), bot.nearest_free_bank()));
+        });
+        assert_eq!(*banks.borrow(), Some((vec![(4, 5)], Some(((4, 5), 1)))));
+    }
+
+    // Coins carried after travelling along the row past two piles
+    fn collected_travelling(collect_en_route: bool) -> usize {
+        let world = TestWorld::new(9, (4, 4))
+            .with((3, 2), Content::Coin(2))
+            .with((5, 1), Content::Coin(3));
+        let mut bot = SaverBot::new(None);
+        bot.config.collect_en_route = collect_en_route;
+        let (coins, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.reach_position(world, 4, 0);
+            *seen.borrow_mut() = bot.count_content(&Content::Coin(0));
+        });
+        let coins = *coins.borrow();
+        coins
+    }
+
+    #[test]
+    fn coins_along_the_way_are_collected_when_enabled() {
+        assert_eq!(collected_travelling(true), 5);
+        assert_eq!(collected_travelling(false), 0);
+    }
+
+    #[test]
+    fn search_results_are_kept_after_the_wander() {
+        let world = TestWorld::new(12, (4, 4)).with((6, 6), Content::Coin(2));
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(8, 8);
+        let (results, seen) = shared(HashMap::new());
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+            *seen.borrow_mut() = bot.last_search_results().clone();
+        });
+        let results = results.borrow();
+        assert!(results.values().any(|coords| !coords.is_empty()), "{:?}", results);
+    }
+
+    // Idle reason and position after a tick with little energy, in the given weather
+    fn tick_in_weather(weather: WeatherType) -> (Option<IdleReason>, (usize, usize)) {
+        let mut world = TestWorld::new(12, (4, 4));
+        world.weather = weather;
+        let mut bot = SaverBot::new(None);
+        bot.config.wait_for_recharge_weather = true;
+        // Whatever energy the bot has is low
+        bot.config.low_energy = 2000;
+        bot.explore_toward(10, 10);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.step(world);
+            *seen.borrow_mut() = Some((bot.idle_reason(), bot.current_position()));
+        });
+        let result = result.borrow().unwrap();
+        result
+    }
+
+    #[test]
+    fn low_energy_waits_for_the_recharge_weather() {
+        assert_eq!(tick_in_weather(WeatherType::Rainy), (Some(IdleReason::Weather), (4, 4)));
+        let (reason, position) = tick_in_weather(WeatherType::Sunny);
+        assert_ne!(reason, Some(IdleReason::Weather));
+        assert_ne!(position, (4, 4));
+    }
+
+    #[test]
+    fn explored_bounds_cover_the_seen_tiles() {
+        let mut bot = SaverBot::new(None);
+        assert_eq!(bot.explored_bounds(), None);
+        bot.record_seen((3, 7), tile(Content::None));
+        bot.record_seen((9, 2), tile(Content::Coin(1)));
+        bot.record_seen((5, 5), tile(Content::None));
+        assert_eq!(bot.explored_bounds(), Some(((3, 2), (9, 7))));
+    }
+
+    #[test]
+    fn saving_nothing_lands_in_the_fallback_state() {
+        let world = TestWorld::new(9, (4, 4));
+        let mut bot = SaverBot::new(None);
+        bot.config.fallback_state = State::RockCollecting;
+        let (state, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            // No coins carried and no bank known
+            bot.set_state(State::Saving);
+            bot.save(world);
+            *seen.borrow_mut() = Some(bot.current_state());
+        });
+        assert_eq!(*state.borrow(), Some(State::RockCollecting));
+    }
+
+    #[test]
+    fn bank_on_grass_is_preferred_to_the_one_in_the_lava() {
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((4, 6), BankStatus::Free, None);
+        bot.add_known_bank((4, 1), BankStatus::Free, None);
+        for coord in [(3, 6), (5, 6), (4, 7), (4, 5)] {
+            bot.record_seen(coord, Tile { tile_type: TileType::Lava, content: Content::None, elevation: 0 });
         }
-        self.reach_position(world, best.0, best.1);
-        let (neighborhoods, (rx, ry)) = where_am_i(self, &world);
-        for x in 0..3 {
-            for y in 0..3 {
-                let tile = &neighborhoods[x][y];
-                if let Some(tile) = tile {
-                    match &tile.content.to_default() {
-                        Content::Bank(_) => {
-                            let dir = if rx + 1 == x {Direction::Up} else if rx - 1 == x {Direction::Down} else if ry + 1 == y {Direction::Left} else {Direction::Right};
-                            return Some(dir);
-                        }
-                        _ => {}
-                    }
+        for coord in [(4, 2), (4, 3), (4, 4)] {
+            bot.record_seen(coord, tile(Content::None));
+        }
+        bot.position = (4, 4);
+        // The bank in the lava is one step closer
+        assert_eq!(bot.nearest_free_bank(), Some(((4, 1), 3)));
+    }
+
+    #[test]
+    fn seen_grows_while_wandering_in_a_tick() {
+        let world = TestWorld::new(16, (4, 4));
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(12, 12);
+        let (sizes, seen) = shared((0, 0, 0));
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.record_neighborhood(world);
+            let before = bot.seen_iter().count();
+            let steps = bot.steps;
+            bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+            *seen.borrow_mut() = (before, bot.seen_iter().count(), bot.steps - steps);
+        });
+        let (before, after, steps) = *sizes.borrow();
+        assert!(steps > 1);
+        assert!(after > before, "{} tiles before, {} after", before, after);
+    }
+
+    #[test]
+    fn replan_fires_every_interval_of_wandering() {
+        let world = TestWorld::new(16, (8, 8));
+        let mut bot = SaverBot::new(None);
+        bot.set_replan_interval(3);
+        let (fired, seen) = shared(vec![]);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            // A bank known at a tile seen empty, only a re-plan forgets it
+            bot.record_seen((0, 0), tile(Content::None));
+            for wander in 1..=7 {
+                bot.add_known_bank((0, 0), BankStatus::Free, None);
+                bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+                if bot.known_banks(BankStatus::Free).is_empty() {
+                    seen.borrow_mut().push(wander);
                 }
             }
+        });
+        assert_eq!(*fired.borrow(), vec![3, 6]);
+    }
+
+    #[test]
+    fn dropped_garbage_leaves_the_backpack() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Garbage(4));
+        let (counts, seen) = shared(None);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            let before = bot.count_content(&Content::Garbage(0));
+            let dropped = bot.drop_content(world, Content::Garbage(0), 3);
+            *seen.borrow_mut() = Some((before, dropped, bot.count_content(&Content::Garbage(0))));
+        });
+        let (before, dropped, after) = counts.borrow().unwrap();
+        assert!(dropped > 0);
+        assert_eq!(after, before - dropped);
+    }
+
+    #[test]
+    fn bank_is_approached_at_the_configured_corner_from_every_side() {
+        let expected = [(Corner::TopLeft, (3, 3)), (Corner::TopRight, (3, 5)), (Corner::BottomLeft, (5, 3)), (Corner::BottomRight, (5, 5))];
+        for (approach_corner, expected) in expected {
+            let mut corners = vec![];
+            for (spawn, direction) in [((5, 4), Direction::Up), ((3, 4), Direction::Down), ((4, 5), Direction::Left), ((4, 3), Direction::Right)] {
+                let world = TestWorld::new(9, spawn).with((4, 4), Content::Bank(0..20));
+                let mut bot = SaverBot::new(None);
+                bot.config.approach_corner = approach_corner;
+                let (corner, seen) = shared(None);
+                run(bot, world, 1, move |bot, world| {
+                    bot.sync_position();
+                    if let Some((x, y)) = bot.approach_position(direction.clone()) {
+                        bot.reach_position(world, x, y);
+                    }
+                    *seen.borrow_mut() = Some(bot.current_position());
+                });
+                corners.push(corner.borrow().unwrap());
+            }
+            assert_eq!(corners, vec![expected; 4], "approaching at {:?}", approach_corner);
         }
-        None
     }
-    fn finish(&mut self, world: &mut World) {
-        // Go to the closest bank
-        let direction = self.go_to_closest_used_bank(world);
 
-        if direction.is_some() && self.get_energy().has_enough_energy(500) {
-           // Reach the bottom left corner of the bank
-           match direction.unwrap() {
-               Direction::Up => {
-                   let _ = go(self, world, Direction::Left);
-               },
-               Direction::Down => {
-                   let _ = go(self, world, Direction::Left);
-                   let _ = go(self, world, Direction::Down);
-                   let _ = go(self, world, Direction::Down);
-                   
-               },
-               Direction::Left => {
-                   let _ = go(self, world, Direction::Down);
-               },
-               Direction::Right => {
-                   let _ = go(self, world, Direction::Down);
-                   let _ = go(self, world, Direction::Left);
-                   let _ = go(self, world, Direction::Left);
-               }
-           } 
-           // Surrond the bank with asphalt
-           self.asphalt_around(world);
+    #[test]
+    fn enclosure_is_laid_out_from_the_configured_corner() {
+        let mut bot = SaverBot::new(None);
+        bot.config.approach_corner = Corner::TopRight;
+        let starts: Vec<_> = bot.enclosure_projects((4, 4)).unwrap().into_iter().map(|(_, tiles, start)| (tiles, start)).collect();
+        // Down the right side, then the bottom going left, up the left side and the top closing on the corner
+        assert_eq!(starts, vec![(3, (3, 5)), (2, (5, 3)), (2, (3, 3)), (2, (3, 4))]);
+        bot.config.approach_corner = Corner::BottomLeft;
+        let starts: Vec<_> = bot.enclosure_projects((4, 4)).unwrap().into_iter().map(|(_, tiles, start)| (tiles, start)).collect();
+        assert_eq!(starts, vec![(3, (3, 3)), (2, (3, 4)), (2, (4, 5)), (2, (5, 3))]);
+        // No room above a bank in the first row
+        assert!(bot.enclosure_projects((0, 4)).is_none());
+    }
+
+    #[test]
+    fn destroy_without_an_add_event_is_recorded() {
+        let mut bot = SaverBot::new(None);
+        bot.handle_event(Event::AddedToBackpack(Content::Coin(2), 2));
+        bot.confirm_pickup(&Content::Coin(2), 2);
+        assert!(bot.missed_pickups().is_empty());
+        // No event arrived for this one
+        bot.confirm_pickup(&Content::Coin(3), 3);
+        assert_eq!(bot.missed_pickups(), &[(Content::Coin(0), 3)]);
+    }
+
+    #[test]
+    fn bank_refusing_every_put_is_given_up() {
+        // The world banks take only coins, so every put of rocks fails
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Rock(2))
+            .with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.bank_contents = vec![Content::Rock(0)];
+        bot.config.max_put_failures = 3;
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (filled, seen) = shared(vec![]);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Right).unwrap();
+            for _ in 0..3 {
+                bot.set_state(State::Saving);
+                bot.save(world);
+                seen.borrow_mut().push(bot.known_banks(BankStatus::Filled));
+            }
+        });
+        assert_eq!(*filled.borrow(), vec![vec![], vec![], vec![(3, 4)]]);
+    }
+
+    // Directions the jitter would prefer, with the given tiles seen around the bot
+    fn unseen_from(spawn: (usize, usize), seen_tiles: Vec<(usize, usize)>) -> Vec<Direction> {
+        let world = TestWorld::new(9, spawn);
+        let (directions, seen) = shared(vec![]);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.record_neighborhood(world);
+            for coord in seen_tiles.iter() {
+                bot.record_seen(*coord, tile(Content::None));
+            }
+            *seen.borrow_mut() = bot.unseen_directions(world);
+        });
+        let directions = directions.borrow().clone();
+        directions
+    }
+
+    #[test]
+    fn jitter_heads_to_the_unseen_side() {
+        let directions = unseen_from((4, 4), vec![(2, 4), (6, 4), (4, 2)]);
+        assert_eq!(format!("{:?}", directions), "[Right]");
+        // Beyond the bottom border there is nothing to see
+        let directions = unseen_from((7, 4), vec![(5, 4), (7, 2), (7, 6)]);
+        assert!(directions.is_empty(), "{:?}", directions);
+    }
+
+    #[test]
+    fn coin_next_to_a_bank_survives_the_wander() {
+        let world = TestWorld::new(12, (4, 4))
+            .with((6, 6), Content::Bank(0..20))
+            .with((6, 5), Content::Coin(2));
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(8, 8);
+        let (coins, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+            *seen.borrow_mut() = Some(bot.count_content(&Content::Coin(0)));
+        });
+        assert_eq!(*coins.borrow(), Some(0));
+    }
+
+    #[test]
+    #[cfg(feature = "audio")]
+    fn given_audio_tool_plays_the_events() {
+        let silent = OxAgAudioTool::new(HashMap::new(), HashMap::new(), HashMap::new()).unwrap();
+        let mut bot = SaverBot::with_audio(None, silent);
+        bot.set_master_volume(0.5);
+        bot.handle_event(Event::Ready);
+        assert!(bot.play_event_sound(&Event::Ready));
+        // Once muted the given tool is not used anymore
+        bot.mute(true);
+        assert!(!bot.play_event_sound(&Event::Ready));
+    }
+
+    // Idle reason of a tick in the state, with the energy of the bot below the default Finish gate
+    fn idle_with_low_energy(state: State) -> Option<IdleReason> {
+        let world = TestWorld::new(9, (4, 4));
+        let mut bot = SaverBot::new(None);
+        bot.force_state(state);
+        let (reason, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            // As if the bot had 160 energy, with the default gates
+            let energy = bot.energy_level();
+            bot.config.state_energy.insert(State::Finish, energy * 500 / 160);
+            bot.config.min_energy = energy * 150 / 160;
+            bot.step(world);
+            *seen.borrow_mut() = bot.idle_reason();
+        });
+        let reason = *reason.borrow();
+        reason
+    }
+
+    #[test]
+    fn enjoying_runs_where_finish_waits() {
+        assert_ne!(idle_with_low_energy(State::Enjoying), Some(IdleReason::LowEnergy));
+        assert_eq!(idle_with_low_energy(State::Finish), Some(IdleReason::LowEnergy));
+    }
 
-           // Go enjoy the thing
-           self.set_state(State::Enjoying);
+    #[test]
+    fn bank_on_the_right_is_tried_first() {
+        let mut bot = SaverBot::new(None);
+        bot.position = (4, 4);
+        assert_eq!(format!("{:?}", bot.deposit_directions((4, 5))[0]), "Right");
+        bot.config.deposit_order = Some(vec![Direction::Down, Direction::Right]);
+        assert_eq!(format!("{:?}", bot.deposit_directions((4, 5))), "[Down, Right]");
+    }
+
+    #[test]
+    fn throughput_is_the_coins_saved_per_tick() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(6))
+            .with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (throughput, seen) = shared(0.0);
+        let mut first = true;
+        run(bot, world, 4, move |bot, world| {
+            if first {
+                // Six coins banked during the first tick
+                first = false;
+                bot.sync_position();
+                destroy(bot, world, Direction::Right).unwrap();
+                bot.set_state(State::Saving);
+                bot.save(world);
+            }
+            bot.step(world);
+            *seen.borrow_mut() = bot.throughput();
+        });
+        assert_eq!(*throughput.borrow(), 1.5);
+    }
+
+    // Coins saved at the bank above, with loose coins around the bot
+    fn saved_with_sweep(sweep_before_deposit: bool) -> usize {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 3), Content::Coin(1))
+            .with((4, 5), Content::Coin(2))
+            .with((5, 4), Content::Coin(3))
+            .with((3, 4), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.sweep_before_deposit = sweep_before_deposit;
+        bot.add_known_bank((3, 4), BankStatus::Free, None);
+        let (saved, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            destroy(bot, world, Direction::Left).unwrap();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            *seen.borrow_mut() = bot.saved_count();
+        });
+        let saved = *saved.borrow();
+        saved
+    }
+
+    #[test]
+    fn loose_coins_are_swept_into_the_bank() {
+        assert_eq!(saved_with_sweep(true), 6);
+        assert_eq!(saved_with_sweep(false), 1);
+    }
+
+    #[test]
+    fn tool_versions_are_the_manifest_requirements() {
+        let versions = SaverBot::new(None).version_info();
+        let manifest = include_str!("../Cargo.toml");
+        let tools = [
+            ("robotics_lib", versions.robotics_lib),
+            ("charting_tools", versions.charting_tools),
+            ("oxagaudiotool", versions.oxagaudiotool),
+            ("recycle_by_ifrustrati", versions.recycle_by_ifrustrati),
+            ("arrusticini_destroy_zone", versions.arrusticini_destroy_zone),
+            ("asfalt_inator", versions.asfalt_inator),
+            ("searchtool_unwrap", versions.searchtool_unwrap),
+        ];
+        assert!(!versions.saver_bot.is_empty());
+        for (tool, version) in tools {
+            assert!(!version.is_empty(), "no version for {}", tool);
+            let line = manifest.lines().find(|line| line.starts_with(&format!("{} =", tool))).unwrap();
+            assert!(line.contains(&format!("version = \"{}\"", version)), "{} is not required at {}", tool, version);
+        }
+    }
+
+    // Found targets the bot goes to in a wander, with the reserve computed from its energy
+    fn targets_visited(reserve: impl Fn(usize) -> usize + 'static) -> usize {
+        let world = TestWorld::new(12, (4, 4))
+            .with((6, 6), Content::Coin(1))
+            .with((6, 8), Content::Coin(1))
+            .with((8, 6), Content::Coin(1));
+        let mut bot = SaverBot::new(None);
+        bot.explore_toward(8, 8);
+        let (visited, seen) = shared(0);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.config.wander_traverse_reserve = reserve(bot.energy_level());
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            bot.wander_in_seach_of(world, vec![Content::Coin(0)]);
+            *seen.borrow_mut() = bot.current_trace.take().unwrap().decisions.iter()
+                .filter(|decision| decision.starts_with("Going to the found target"))
+                .count();
+        });
+        let visited = *visited.borrow();
+        visited
+    }
+
+    #[test]
+    fn low_reserve_visits_more_targets() {
+        // A reserve as high as the energy stops after the first trip
+        let high = targets_visited(|energy| energy);
+        let low = targets_visited(|_| 0);
+        assert!(low > high, "{} targets with a low reserve, {} with a high one", low, high);
+    }
+
+    #[test]
+    fn idle_reason_tells_the_energy_is_low() {
+        let world = TestWorld::new(9, (4, 4));
+        let (reason, seen) = shared(None);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            // The default gate, with the energy below it
+            bot.config.min_energy = bot.energy_level() + 1;
+            bot.step(world);
+            *seen.borrow_mut() = bot.idle_reason();
+        });
+        assert_eq!(*reason.borrow(), Some(IdleReason::LowEnergy));
+    }
+
+    #[test]
+    fn enough_known_coins_stop_the_exploration() {
+        let world = TestWorld::new(12, (4, 4))
+            .with((4, 8), Content::Coin(2))
+            .with((9, 9), Content::Coin(2));
+        let mut bot = SaverBot::new(None);
+        bot.config.enough_known_tiles = Some(2);
+        bot.record_seen((4, 8), tile(Content::Coin(2)));
+        bot.record_seen((9, 9), tile(Content::Coin(2)));
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            bot.coin_collect(world);
+            let searches = bot.current_trace.take().unwrap().decisions.iter()
+                .filter(|decision| decision.starts_with("Searching for"))
+                .count();
+            *seen.borrow_mut() = Some((searches, bot.current_position(), bot.count_content(&Content::Coin(0))));
+        });
+        // The closest coin is collected without searching around
+        assert_eq!(*result.borrow(), Some((0, (4, 7), 2)));
+    }
+
+    #[test]
+    fn adjacent_scan_matches_the_neighborhood() {
+        let world = TestWorld::new(3, (1, 1))
+            .with((0, 1), Content::Coin(1))
+            .with((2, 1), Content::Rock(1))
+            .with((1, 0), Content::Tree(1))
+            .with((1, 2), Content::Bank(0..20))
+            .with((0, 0), Content::Garbage(1));
+        let (neighbors, seen) = shared(vec![]);
+        run(SaverBot::new(None), world, 1, move |bot, world| {
+            *seen.borrow_mut() = bot.scan_adjacent(world).iter()
+                .map(|neighbor| neighbor.as_ref().map(|(direction, tile)| format!("{:?} {:?}", direction, tile.content)))
+                .collect();
+        });
+        // The corners are not neighbors
+        assert_eq!(*neighbors.borrow(), vec![
+            Some(String::from("Up Coin(1)")),
+            Some(String::from("Down Rock(1)")),
+            Some(String::from("Left Tree(1)")),
+            Some(String::from("Right Bank(0..20)")),
+        ]);
+    }
+
+    #[test]
+    fn oversized_pile_is_finished_after_the_save_trip() {
+        let world = TestWorld::new(9, (4, 4))
+            .with((4, 5), Content::Coin(25))
+            .with((2, 4), Content::Bank(0..40));
+        let mut bot = SaverBot::new(None);
+        bot.add_known_bank((2, 4), BankStatus::Free, None);
+        let (result, seen) = shared(None);
+        run(bot, world, 1, move |bot, world| {
+            bot.sync_position();
+            bot.destroy_area(world);
+            let left = bot.pending_harvest.clone();
+            bot.set_state(State::Saving);
+            bot.save(world);
+            let saved = bot.saved_count();
+            bot.coin_collect(world);
+            *seen.borrow_mut() = Some((left, saved, bot.count_content(&Content::Coin(0)), bot.pending_harvest.len()));
+        });
+        // The backpack holds 20 coins, the other 5 are taken on the way back
+        assert_eq!(*result.borrow(), Some((vec![((4, 5), 5)], 20, 5, 0)));
+    }
+
+    #[test]
+    #[cfg(feature = "audio")]
+    fn state_change_sound_is_played_unless_muted() {
+        let mut sounds = vec![];
+        for muted in [false, true] {
+            let mut bot = SaverBot::new(None);
+            bot.mute(muted);
+            bot.set_state_change_sound(State::Saving, OxAgSoundConfig::new_with_volume("assets/default/event/event_ready.ogg", 1.0));
+            bot.current_trace = Some(TickTrace::new(0, State::CoinCollecting));
+            bot.set_state(State::Saving);
+            sounds.push(bot.current_trace.take().unwrap().actions.iter().filter(|action| action.starts_with("sound of Saving")).count());
         }
+        assert_eq!(sounds, vec![1, 0]);
+    }
+
+    #[test]
+    fn used_banks_stay_bounded_keeping_the_top_one() {
+        let mut bot = SaverBot::new(None);
+        bot.config.max_used_banks = 2;
+        for (tick, (bank, coins)) in [((1, 1), 30), ((2, 2), 5), ((3, 3), 8), ((4, 4), 2)].into_iter().enumerate() {
+            bot.used_banks.insert(bank, coins);
+            bot.used_banks_tick.insert(bank, tick);
+            bot.evict_used_banks(bank);
+            assert!(bot.used_banks.len() <= 2);
+        }
+        // The most deposited bank and the one just used are kept
+        let mut kept: Vec<(usize, usize)> = bot.used_banks.keys().cloned().collect();
+        kept.sort();
+        assert_eq!(kept, vec![(1, 1), (4, 4)]);
+        assert_eq!(bot.best_used_bank(false), Some((1, 1)));
+    }
+
+    #[test]
+    fn waiting_tick_without_changes_skips_the_lookups() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.trace = true;
+        let (found, notified) = shared(0);
+        bot.set_on_bank_found(move |_| *notified.borrow_mut() += 1);
+        let (decisions, seen) = shared(vec![]);
+        run(bot, world, 2, move |bot, world| {
+            let gate = bot.energy_level() + 1;
+            bot.config.state_energy.insert(State::CoinCollecting, gate);
+            // Forgotten, so a new look around would find the bank again
+            let _ = bot.free_banks.remove(&bank_key(), ChartedCoordinate(4, 5));
+            bot.step(world);
+            seen.borrow_mut().push(bot.last_trace().unwrap().decisions.last().cloned());
+        });
+        assert_eq!(*found.borrow(), 1);
+        assert_eq!(*decisions.borrow(), vec![
+            Some(String::from("Waiting for energy")),
+            Some(String::from("Waiting for energy, nothing changed")),
+        ]);
+    }
+
+    #[test]
+    fn recharge_below_the_gate_keeps_the_waiting_fast() {
+        let world = TestWorld::new(9, (4, 4)).with((4, 5), Content::Bank(0..20));
+        let mut bot = SaverBot::new(None);
+        bot.config.trace = true;
+        let (decisions, seen) = shared(vec![]);
+        let mut first = true;
+        run(bot, world, 2, move |bot, world| {
+            if !first {
+                // The recharge clears the waiting flag, but the energy is still below the gate
+                bot.handle_event(Event::EnergyRecharged(1));
+            }
+            first = false;
+            let gate = bot.energy_level() + 1;
+            bot.config.state_energy.insert(State::CoinCollecting, gate);
+            bot.step(world);
+            seen.borrow_mut().push(bot.last_trace().unwrap().decisions.last().cloned());
+        });
+        assert_eq!(*decisions.borrow(), vec![
+            Some(String::from("Waiting for energy")),
+            Some(String::from("Waiting for energy, nothing changed")),
+        ]);
     }
 }
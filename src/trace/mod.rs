@@ -0,0 +1,25 @@
+use crate::State;
+
+/// What the bot did during a single tick, recorded only if `config.trace` is enabled
+/// - tick: The tick the trace refers to
+/// - state: The state the bot was in when the tick started
+/// - decisions: The choices the bot made, in order
+/// - actions: The actions attempted with their result, in order
+#[derive(Debug, Clone)]
+pub struct TickTrace {
+    pub tick: usize,
+    pub state: State,
+    pub decisions: Vec<String>,
+    pub actions: Vec<String>
+}
+
+impl TickTrace {
+    pub fn new(tick: usize, state: State) -> Self {
+        TickTrace {
+            tick,
+            state,
+            decisions: vec![],
+            actions: vec![]
+        }
+    }
+}
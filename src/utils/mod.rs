@@ -2,17 +2,27 @@
 use searchtool_unwrap::SearchDirection;
 
 // Public library
-use robotics_lib::world::tile::Content;
+use robotics_lib::interface::Direction;
+use robotics_lib::world::tile::{Content, Tile, TileType};
 
 // Standard library
+use std::collections::HashMap;
 use std::ops::Range;
 
 pub const ROCK_LOOKING_FOR: [Content; 1] = [Content::Rock(0)];
 pub const COIN_LOOKING_FOR: [Content; 3] = [Content::Coin(0), Content::Rock(0), Content::Garbage(0)];
-pub const BANK_LOOKING_FOR: [Content; 1] = [Content::Bank(Range { start: 0, end: 0 })];
+pub const BANK_LOOKING_FOR: [Content; 1] = [bank_key()];
+pub const SEEN_BUCKET_SIZE: usize = 8;
+pub const ASPHALT_PROJECTS: usize = 4;
 pub const DIRECTIONS: [SearchDirection; 4] = [SearchDirection::BottomLeft, SearchDirection::BottomRight, 
                                                 SearchDirection::TopLeft, SearchDirection::TopRight];
 
+/// Key of the banks in the charted maps, whatever range the bank tile has
+/// Banks are always stored and looked up with this key
+pub const fn bank_key() -> Content {
+    Content::Bank(Range { start: 0, end: 0 })
+}
+
 pub fn clone_direction(direction: &SearchDirection) -> SearchDirection {
     match direction {
         | SearchDirection::BottomLeft => SearchDirection::BottomLeft,
@@ -20,4 +30,145 @@ pub fn clone_direction(direction: &SearchDirection) -> SearchDirection {
         | SearchDirection::TopLeft => SearchDirection::TopLeft,
         | SearchDirection::TopRight => SearchDirection::TopRight,
     }
-}
\ No newline at end of file
+}
+
+/// Position of the search direction inside `DIRECTIONS`
+pub fn quadrant_index(direction: &SearchDirection) -> usize {
+    match direction {
+        | SearchDirection::BottomLeft => 0,
+        | SearchDirection::BottomRight => 1,
+        | SearchDirection::TopLeft => 2,
+        | SearchDirection::TopRight => 3,
+    }
+}
+
+/// Index of the robot inside the neighborhood returned by `where_am_i`
+/// At the map edge the neighborhood can be truncated, so the robot is not always in the middle
+pub fn neighborhood_center(tiles: &[Vec<Option<Tile>>], position: (usize, usize)) -> (usize, usize) {
+    let row = if tiles.len() < 3 && position.0 == 0 { 0 } else { 1 };
+    let cols = tiles.get(row).map(|r| r.len()).unwrap_or(0);
+    let col = if cols < 3 && position.1 == 0 { 0 } else { 1 };
+    (row, col)
+}
+
+/// World coordinates and tiles of the neighborhood returned by `where_am_i`,
+/// without the missing cells, so it works with truncated neighborhoods too
+pub fn neighborhood_cells(tiles: &[Vec<Option<Tile>>], position: (usize, usize)) -> Vec<((usize, usize), Tile)> {
+    let center = neighborhood_center(tiles, position);
+    let mut cells = vec![];
+    for (i, row) in tiles.iter().enumerate() {
+        for (j, tile) in row.iter().enumerate() {
+            if let (Some(tile), Some(x), Some(y)) = (tile, (position.0 + i).checked_sub(center.0), (position.1 + j).checked_sub(center.1)) {
+                cells.push(((x, y), tile.clone()));
+            }
+        }
+    }
+    cells
+}
+
+/// Direction to go from the center of the neighborhood to the cell (i, j)
+/// Diagonal cells and the center itself can't be reached with a single direction
+pub fn direction_from_offset(center: (usize, usize), cell: (usize, usize)) -> Option<Direction> {
+    if cell.1 == center.1 && cell.0 + 1 == center.0 {
+        Some(Direction::Up)
+    } else if cell.1 == center.1 && cell.0 == center.0 + 1 {
+        Some(Direction::Down)
+    } else if cell.0 == center.0 && cell.1 + 1 == center.1 {
+        Some(Direction::Left)
+    } else if cell.0 == center.0 && cell.1 == center.1 + 1 {
+        Some(Direction::Right)
+    } else {
+        None
+    }
+}
+
+/// Quantity of a content in the backpack contents, whatever quantity the keys have
+pub fn count_in(contents: &HashMap<Content, usize>, content: &Content) -> usize {
+    let target = content.to_default();
+    contents.iter()
+        .filter(|(key, _)| key.to_default() == target)
+        .map(|(_, quantity)| *quantity)
+        .sum()
+}
+
+/// Manhattan distance between two points of the world
+pub fn manhattan(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Search quadrant that contains the point `to`, as seen from `from`
+pub fn search_direction_toward(from: (usize, usize), to: (usize, usize)) -> SearchDirection {
+    match (to.0 > from.0, to.1 > from.1) {
+        (true, true) => SearchDirection::BottomRight,
+        (true, false) => SearchDirection::BottomLeft,
+        (false, true) => SearchDirection::TopRight,
+        (false, false) => SearchDirection::TopLeft,
+    }
+}
+
+/// Single step that gets closer to `to`, along the axis with the biggest distance
+pub fn direction_toward(from: (usize, usize), to: (usize, usize)) -> Option<Direction> {
+    if from == to {
+        None
+    } else if from.0.abs_diff(to.0) >= from.1.abs_diff(to.1) {
+        Some(if to.0 > from.0 { Direction::Down } else { Direction::Up })
+    } else {
+        Some(if to.1 > from.1 { Direction::Right } else { Direction::Left })
+    }
+}
+
+/// Bucket of the seen spatial index that contains the coordinate
+pub fn seen_bucket(coord: (usize, usize)) -> (usize, usize) {
+    (coord.0 / SEEN_BUCKET_SIZE, coord.1 / SEEN_BUCKET_SIZE)
+}
+
+/// Tells if the robot can step on the tile type
+pub fn is_walkable(tile_type: &TileType) -> bool {
+    !matches!(tile_type, TileType::DeepWater | TileType::Wall)
+}
+
+/// Position reached going one step in the direction, None if it would be outside of the map
+pub fn step_from(position: (usize, usize), direction: &Direction) -> Option<(usize, usize)> {
+    match direction {
+        Direction::Up => Some((position.0.checked_sub(1)?, position.1)),
+        Direction::Down => Some((position.0 + 1, position.1)),
+        Direction::Left => Some((position.0, position.1.checked_sub(1)?)),
+        Direction::Right => Some((position.0, position.1 + 1)),
+    }
+}
+
+/// Position moved by the rows and columns, None if it would be outside of the map
+pub fn offset_from(position: (usize, usize), offset: (isize, isize)) -> Option<(usize, usize)> {
+    Some((position.0.checked_add_signed(offset.0)?, position.1.checked_add_signed(offset.1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(content: Content) -> Option<Tile> {
+        Some(Tile { tile_type: TileType::Grass, content, elevation: 0 })
+    }
+
+    #[test]
+    fn bank_next_to_the_bot_at_the_top_edge() {
+        // The row above the bot is outside of the map, so the neighborhood has only two rows
+        let tiles = vec![
+            vec![tile(Content::None), tile(Content::None), tile(Content::Bank(0..10))],
+            vec![tile(Content::None), tile(Content::None), tile(Content::None)],
+        ];
+        let center = neighborhood_center(&tiles, (0, 4));
+        assert_eq!(center, (0, 1));
+        assert!(matches!(direction_from_offset(center, (0, 2)), Some(Direction::Right)));
+        assert!(matches!(direction_from_offset(center, (1, 1)), Some(Direction::Down)));
+        assert!(direction_from_offset(center, (1, 2)).is_none());
+    }
+
+    #[test]
+    fn coins_are_counted_under_any_key() {
+        let contents = HashMap::from([(Content::Coin(5), 3), (Content::Coin(0), 2), (Content::Rock(5), 4)]);
+        assert_eq!(count_in(&contents, &Content::Coin(0)), 5);
+        assert_eq!(count_in(&contents, &Content::Coin(7)), 5);
+        assert_eq!(count_in(&contents, &Content::Garbage(0)), 0);
+    }
+}
@@ -0,0 +1,33 @@
+/// Versions of the bot and of the tools it needs, to tell them in the bug reports
+/// The tools don't expose their version, so these are the minimum versions required by
+/// the manifest: the ones really built can be newer, Cargo.lock tells which
+/// - saver_bot: The version of the bot itself
+/// - robotics_lib: The common library
+/// - the other fields: The tools used by the bot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolVersions {
+    pub saver_bot: &'static str,
+    pub robotics_lib: &'static str,
+    pub charting_tools: &'static str,
+    pub oxagaudiotool: &'static str,
+    pub recycle_by_ifrustrati: &'static str,
+    pub arrusticini_destroy_zone: &'static str,
+    pub asfalt_inator: &'static str,
+    pub searchtool_unwrap: &'static str
+}
+
+impl ToolVersions {
+    /// Versions required by the manifest, kept in sync with Cargo.toml
+    pub fn required() -> Self {
+        ToolVersions {
+            saver_bot: env!("CARGO_PKG_VERSION"),
+            robotics_lib: "0.1.21",
+            charting_tools: "1.0.0",
+            oxagaudiotool: "0.2.0",
+            recycle_by_ifrustrati: "0.1.0",
+            arrusticini_destroy_zone: "0.1.0",
+            asfalt_inator: "0.1.0",
+            searchtool_unwrap: "0.1.1"
+        }
+    }
+}